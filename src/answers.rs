@@ -0,0 +1,390 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::runner::{self, PartResult, Status};
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Known-correct answers, read from a small subset of TOML: one `[dayN]` table per day holding
+/// `partN` keys with integer or string values. Comments are allowed, and a table or key may not
+/// appear twice.
+///
+/// ```toml
+/// [day1]
+/// part1 = 24000 # from the real input
+/// part2 = "45000"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(HashMap<(u8, u8), String>);
+
+pub enum Verdict {
+    Match,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// The solver failed, whether or not an answer is recorded.
+    Failed {
+        expected: Option<String>,
+        error: String,
+    },
+    /// An answer is recorded, but there is no input to check it against.
+    Missing {
+        expected: String,
+    },
+    /// Solved, but there is no recorded answer to compare with.
+    Unrecorded,
+    /// No input and no recorded answer.
+    Skipped,
+}
+
+impl Verdict {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Match => "match",
+            Verdict::Mismatch { .. } => "mismatch",
+            Verdict::Failed { .. } => "failed",
+            Verdict::Missing { .. } => "missing",
+            Verdict::Unrecorded => "unrecorded",
+            Verdict::Skipped => "skipped",
+        }
+    }
+
+    /// Whether this verdict makes verification fail.
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Verdict::Mismatch { .. } | Verdict::Failed { .. } | Verdict::Missing { .. }
+        )
+    }
+}
+
+fn parse_key(key: &str, prefix: &str) -> Option<u8> {
+    key.strip_prefix(prefix)?.parse().ok()
+}
+
+/// Whether only whitespace or a comment is left on the line.
+fn is_line_end(rest: &str) -> bool {
+    let rest = rest.trim_start();
+    rest.is_empty() || rest.starts_with('#')
+}
+
+/// Parses a TOML integer: an optional sign, then digits without a leading zero, optionally
+/// grouped with single underscores.
+fn parse_integer(text: &str) -> Option<i64> {
+    let digits = text.strip_prefix(['+', '-']).unwrap_or(text);
+    let well_formed = digits
+        .split('_')
+        .all(|group| !group.is_empty() && group.bytes().all(|b| b.is_ascii_digit()))
+        && (digits == "0" || !digits.starts_with('0'));
+    if !well_formed {
+        return None;
+    }
+    text.replace('_', "").parse().ok()
+}
+
+/// Splits a string or integer value off the start of `text`, returning it and the rest of the
+/// line. Integers are returned in canonical form, so `+24_000` is recorded as `24000`.
+fn parse_value(text: &str) -> Option<(String, &str)> {
+    if let Some(literal) = text.strip_prefix('\'') {
+        let (value, rest) = literal.split_once('\'')?;
+        return Some((value.to_string(), rest));
+    }
+    if let Some(quoted) = text.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '"' => return Some((value, &quoted[idx + 1..])),
+                '\\' => value.push(match chars.next()?.1 {
+                    '"' => '"',
+                    '\\' => '\\',
+                    'n' => '\n',
+                    't' => '\t',
+                    _ => return None,
+                }),
+                _ => value.push(c),
+            }
+        }
+        return None;
+    }
+    let end = text
+        .find(|c: char| c.is_whitespace() || c == '#')
+        .unwrap_or(text.len());
+    let (number, rest) = text.split_at(end);
+    Some((parse_integer(number)?.to_string(), rest))
+}
+
+impl std::str::FromStr for Answers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = HashMap::new();
+        let mut days = HashSet::new();
+        let mut day = None;
+        for (idx, line) in s.lines().enumerate() {
+            let line_number = idx + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let section = header
+                    .split_once(']')
+                    .filter(|(_, rest)| is_line_end(rest))
+                    .and_then(|(section, _)| parse_key(section.trim(), "day"))
+                    .ok_or(format!("line {line_number}: invalid section {line}"))?;
+                if !days.insert(section) {
+                    return Err(format!(
+                        "line {line_number}: duplicate section [day{section}]"
+                    ));
+                }
+                day = Some(section);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(format!("line {line_number}: expected `partN = answer`"))?;
+            let day = day.ok_or(format!(
+                "line {line_number}: answer outside of a [dayN] section"
+            ))?;
+            let part = parse_key(key.trim(), "part")
+                .ok_or(format!("line {line_number}: invalid key {}", key.trim()))?;
+            let value = parse_value(value.trim())
+                .filter(|(_, rest)| is_line_end(rest))
+                .map(|(value, _)| value)
+                .ok_or(format!(
+                    "line {line_number}: invalid value {}",
+                    value.trim()
+                ))?;
+            if answers.insert((day, part), value).is_some() {
+                return Err(format!(
+                    "line {line_number}: duplicate answer for day {day} part {part}"
+                ));
+            }
+        }
+
+        Ok(Answers(answers))
+    }
+}
+
+impl Answers {
+    pub fn path(inputs: &Path) -> PathBuf {
+        inputs.join(ANSWERS_FILE)
+    }
+
+    pub fn load(inputs: &Path) -> Result<Answers, String> {
+        let path = Self::path(inputs);
+        // I/O errors already name the file.
+        let data = crate::util::read_file(&path).map_err(|err| err.to_string())?;
+        data.parse()
+            .map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    pub fn expected(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn check(&self, result: &PartResult) -> Verdict {
+        let expected = self.expected(result.day, result.part).map(str::to_string);
+        match (&result.status, expected) {
+            (Status::Failed(err), expected) => Verdict::Failed {
+                expected,
+                error: err.to_string(),
+            },
+            (Status::Skipped, Some(expected)) => Verdict::Missing { expected },
+            (Status::Skipped, None) => Verdict::Skipped,
+            (Status::Solved(_), None) => Verdict::Unrecorded,
+            (Status::Solved(answer), Some(expected)) if *answer == expected => Verdict::Match,
+            (Status::Solved(answer), Some(expected)) => Verdict::Mismatch {
+                expected,
+                actual: answer.clone(),
+            },
+        }
+    }
+}
+
+/// One part's result, checked against its recorded answer.
+pub struct Check {
+    pub result: PartResult,
+    pub expected: Option<String>,
+    pub verdict: Verdict,
+}
+
+/// Every part of every day, checked by [`verify`].
+pub struct Verification {
+    pub checks: Vec<Check>,
+}
+
+impl Verification {
+    /// How many parts failed verification.
+    pub fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.verdict.is_failure())
+            .count()
+    }
+}
+
+/// Runs every day against `inputs` and checks each part against the answers recorded in
+/// [`ANSWERS_FILE`] there.
+pub fn verify(inputs: &Path) -> Result<Verification, String> {
+    let answers = Answers::load(inputs)?;
+    let checks = runner::run_all(inputs)
+        .into_iter()
+        .map(|result| Check {
+            expected: answers
+                .expected(result.day, result.part)
+                .map(str::to_string),
+            verdict: answers.check(&result),
+            result,
+        })
+        .collect();
+    Ok(Verification { checks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answers, Verdict};
+    use crate::error::Error;
+    use crate::runner::{PartResult, Status};
+
+    const ANSWERS: &str = "# known answers
+[day1]
+part1 = 24000
+part2 = \"45000\"
+
+[day05]
+part2 = \"MCD\"
+";
+
+    fn result(day: u8, part: u8, status: Status) -> PartResult {
+        PartResult {
+            day,
+            part,
+            status,
+            parse_elapsed: None,
+            elapsed: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+        assert_eq!(answers.expected(1, 1), Some("24000"));
+        assert_eq!(answers.expected(1, 2), Some("45000"));
+        assert_eq!(answers.expected(5, 2), Some("MCD"));
+        assert_eq!(answers.expected(5, 1), None);
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        assert!("part1 = 3".parse::<Answers>().is_err());
+        assert!("[night1]".parse::<Answers>().is_err());
+        assert!("[day1]\npart1 = bogus".parse::<Answers>().is_err());
+        assert!("[day1]\npart1 = 1-2-3".parse::<Answers>().is_err());
+        assert!("[day1]\npart1 = 007".parse::<Answers>().is_err());
+        assert!("[day1]\npart1 = \"MCD\" extra".parse::<Answers>().is_err());
+        assert!("[day1]\npart1 = 1\npart1 = 2".parse::<Answers>().is_err());
+        assert!("[day1]\n[day01]".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn test_parse_answers_comments_and_values() {
+        let answers: Answers = "[day1] # calories
+part1 = 24000 # from 2022
+part2 = -1_000
+[day5]
+part1 = \"C#M\\\"Z\" # quoted
+part2 = 'M#CD'
+"
+        .parse()
+        .unwrap();
+        assert_eq!(answers.expected(1, 1), Some("24000"));
+        assert_eq!(answers.expected(1, 2), Some("-1000"));
+        assert_eq!(answers.expected(5, 1), Some("C#M\"Z"));
+        assert_eq!(answers.expected(5, 2), Some("M#CD"));
+    }
+
+    #[test]
+    fn test_check() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+        let solved = |answer: &str| Status::Solved(answer.to_string());
+        assert!(matches!(
+            answers.check(&result(1, 1, solved("24000"))),
+            Verdict::Match
+        ));
+        assert!(matches!(
+            answers.check(&result(5, 2, solved("CMZ"))),
+            Verdict::Mismatch { .. }
+        ));
+        assert!(matches!(
+            answers.check(&result(1, 2, Status::Failed(Error::solve(1, "no elves")))),
+            Verdict::Failed { .. }
+        ));
+        assert!(matches!(
+            answers.check(&result(3, 1, solved("157"))),
+            Verdict::Unrecorded
+        ));
+        assert!(matches!(
+            answers.check(&result(3, 1, Status::Skipped)),
+            Verdict::Skipped
+        ));
+    }
+
+    #[test]
+    fn test_check_failures() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+        // A recorded answer with no input to check it against.
+        let missing = answers.check(&result(1, 2, Status::Skipped));
+        assert!(matches!(&missing, Verdict::Missing { expected } if expected == "45000"));
+        assert!(missing.is_failure());
+        // A failed solve with nothing recorded.
+        let failed = answers.check(&result(
+            3,
+            1,
+            Status::Failed(Error::solve(3, "bad rucksack")),
+        ));
+        assert!(matches!(&failed, Verdict::Failed { expected: None, .. }));
+        assert!(failed.is_failure());
+
+        assert!(!answers.check(&result(3, 1, Status::Skipped)).is_failure());
+        let unrecorded = answers.check(&result(3, 1, Status::Solved("157".to_string())));
+        assert!(!unrecorded.is_failure());
+    }
+
+    #[test]
+    fn test_verify() {
+        let inputs = std::env::temp_dir().join(format!("aoc2022-verify-{}", std::process::id()));
+        std::fs::create_dir_all(&inputs).unwrap();
+        std::fs::write(
+            super::Answers::path(&inputs),
+            "[day3]\npart1 = 157\n[day4]\npart1 = 2\npart2 = 1\n",
+        )
+        .unwrap();
+        std::fs::write(
+            crate::runner::input_path(&inputs, 4),
+            "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n",
+        )
+        .unwrap();
+        let verification = super::verify(&inputs);
+        std::fs::remove_dir_all(&inputs).unwrap();
+
+        let verification = verification.unwrap();
+        let verdict = |day: u8, part: u8| {
+            verification
+                .checks
+                .iter()
+                .find(|check| check.result.day == day && check.result.part == part)
+                .map(|check| check.verdict.name())
+                .unwrap()
+        };
+        assert_eq!(verdict(3, 1), "missing");
+        assert_eq!(verdict(3, 2), "skipped");
+        assert_eq!(verdict(4, 1), "match");
+        assert_eq!(verdict(4, 2), "mismatch");
+        assert_eq!(verification.failures(), 2);
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::runner::{self, Day, PARTS};
+
+pub const DEFAULT_ITERATIONS: usize = 100;
+
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        let count = samples.len();
+        let median = if count.is_multiple_of(2) {
+            (samples[count / 2 - 1] + samples[count / 2]) / 2
+        } else {
+            samples[count / 2]
+        };
+        // Nearest-rank percentile.
+        let p95_rank = (count * 95).div_ceil(100);
+        Some(Stats {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / count as u32,
+            p95: samples[p95_rank - 1],
+        })
+    }
+}
+
+pub struct BenchResult {
+    pub day: u8,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub parts: Vec<(u8, Result<Stats>)>,
+}
+
+fn sample<T, F: FnMut() -> T>(iterations: usize, mut f: F) -> (Vec<Duration>, T) {
+    let mut samples = Vec::with_capacity(iterations);
+    let mut last = None;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let value = f();
+        samples.push(start.elapsed());
+        last = Some(value);
+    }
+
+    (samples, last.expect("at least one iteration"))
+}
+
+/// Times the parse step and each part of `day` separately, `iterations` times each. Parts that
+/// fail on their first run are reported with their error instead of timings.
+pub fn bench(day: &Day, input: &str, iterations: usize) -> Result<BenchResult> {
+    let iterations = iterations.max(1);
+    let (mut parse_samples, parsed) = sample(iterations, || day.parse(input));
+    let parsed = parsed?;
+    let parse = Stats::from_samples(&mut parse_samples).expect("at least one sample");
+
+    let parts = PARTS
+        .iter()
+        .map(|&part| {
+            if let Err(err) = parsed.solve(part) {
+                return (part, Err(err));
+            }
+            let (mut samples, _) = sample(iterations, || parsed.solve(part));
+            let stats = Stats::from_samples(&mut samples).expect("at least one sample");
+            (part, Ok(stats))
+        })
+        .collect();
+
+    Ok(BenchResult {
+        day: day.number,
+        iterations,
+        parse,
+        parts,
+    })
+}
+
+/// Loads `path` and benchmarks `day` on it. Errors name the file they came from.
+pub fn bench_file(day: u8, path: &Path, iterations: usize) -> Result<BenchResult> {
+    let Some(solution) = runner::find_day(day) else {
+        return Err(Error::Unsolved { day, part: 1 });
+    };
+    let data = solution.load_input(path)?;
+    bench(solution, &data, iterations).map_err(|err| err.in_file(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stats;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stats_from_samples() {
+        let mut samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = Stats::from_samples(&mut samples).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(10_500));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+    }
+
+    #[test]
+    fn test_stats_from_no_samples() {
+        assert!(Stats::from_samples(&mut []).is_none());
+    }
+
+    #[test]
+    fn test_bench() {
+        let day = crate::runner::find_day(4).unwrap();
+        let result = super::bench(day, "2-4,6-8\n2-8,3-7", 5).unwrap();
+        assert_eq!(result.iterations, 5);
+        assert_eq!(result.parts.len(), 2);
+        assert!(result.parts.iter().all(|(_, stats)| stats.is_ok()));
+    }
+}
@@ -0,0 +1,302 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use adventofcode2022::bench::DEFAULT_ITERATIONS;
+
+pub enum Command {
+    Run {
+        day: u8,
+        part: u8,
+        path: Option<PathBuf>,
+    },
+    All {
+        inputs: PathBuf,
+    },
+    Verify {
+        inputs: PathBuf,
+    },
+    Bench {
+        day: u8,
+        iterations: usize,
+        path: Option<PathBuf>,
+    },
+    Stats {
+        path: Option<PathBuf>,
+    },
+}
+
+impl Command {
+    /// The day and part the command is about, where it names them.
+    pub fn target(&self) -> (Option<u8>, Option<u8>) {
+        match self {
+            Command::Run { day, part, .. } => (Some(*day), Some(*part)),
+            Command::Bench { day, .. } => (Some(*day), None),
+            Command::Stats { .. } => (Some(1), None),
+            Command::All { .. } | Command::Verify { .. } => (None, None),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format: {s} (expected text or json)")),
+        }
+    }
+}
+
+pub struct Args {
+    pub command: Command,
+    pub format: Format,
+}
+
+pub const DEFAULT_INPUTS_DIR: &str = "inputs";
+
+fn parse_number<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or(format!("{flag} requires a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {flag}: {value}"))
+}
+
+fn parse_run<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(parse_number("--day", args.next())?),
+            "--part" | "-p" => part = Some(parse_number("--part", args.next())?),
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    Ok(Command::Run {
+        day: day.ok_or("--day is required")?,
+        part: part.ok_or("--part is required")?,
+        path,
+    })
+}
+
+fn parse_bench<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut day = None;
+    let mut iterations = DEFAULT_ITERATIONS;
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(parse_number("--day", args.next())?),
+            "--iterations" | "-n" => iterations = parse_number("--iterations", args.next())?,
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    Ok(Command::Bench {
+        day: day.ok_or("--day is required")?,
+        iterations,
+        path,
+    })
+}
+
+fn parse_stats<I: Iterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut path = None;
+    for arg in args {
+        match path {
+            None => path = Some(PathBuf::from(arg)),
+            Some(_) => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    Ok(Command::Stats { path })
+}
+
+fn parse_inputs<I: Iterator<Item = String>>(mut args: I) -> Result<PathBuf, String> {
+    let mut inputs = PathBuf::from(DEFAULT_INPUTS_DIR);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--inputs" | "-i" => {
+                inputs = PathBuf::from(args.next().ok_or("--inputs requires a value")?)
+            }
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    Ok(inputs)
+}
+
+fn parse_command<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    match args.next().as_deref() {
+        Some("run") => parse_run(args),
+        Some("all") => Ok(Command::All {
+            inputs: parse_inputs(args)?,
+        }),
+        Some("verify") => Ok(Command::Verify {
+            inputs: parse_inputs(args)?,
+        }),
+        Some("bench") => parse_bench(args),
+        Some("stats") => parse_stats(args),
+        Some(command) => Err(format!("unknown command: {command}")),
+        None => Err("no command given".to_string()),
+    }
+}
+
+/// Parses the command line. `--format` is accepted anywhere, for every command.
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+    let mut format = Format::Text;
+    let mut rest = vec![];
+    while let Some(arg) = args.next() {
+        if arg == "--format" || arg == "-f" {
+            format = args.next().ok_or("--format requires a value")?.parse()?;
+        } else {
+            rest.push(arg);
+        }
+    }
+
+    Ok(Args {
+        command: parse_command(rest.into_iter())?,
+        format,
+    })
+}
+
+pub fn usage(program: &str) -> String {
+    format!(
+        "USAGE: {program} run --day N --part N [filename | -]
+       {program} all [--inputs DIR]
+       {program} verify [--inputs DIR]
+       {program} bench --day N [--iterations N] [filename | -]
+       {program} stats [filename | -]    (day 1 calorie statistics)
+
+Every command accepts --format text|json (default: text)."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args, parse_command, Command, Format};
+    use std::path::PathBuf;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(|arg| arg.to_string())
+    }
+
+    #[test]
+    fn test_parse_run() {
+        match parse_command(args("run --day 4 --part 2 input.txt")) {
+            Ok(Command::Run { day, part, path }) => {
+                assert_eq!(day, 4);
+                assert_eq!(part, 2);
+                assert_eq!(path, Some(PathBuf::from("input.txt")));
+            }
+            Ok(_) => panic!("expected the run command"),
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_run_default_path() {
+        match parse_command(args("run --day 4 --part 2")) {
+            Ok(Command::Run { path, .. }) => assert_eq!(path, None),
+            _ => panic!("expected the run command"),
+        }
+        match parse_command(args("run --day 4 --part 2 -")) {
+            Ok(Command::Run { path, .. }) => assert_eq!(path, Some(PathBuf::from("-"))),
+            _ => panic!("expected the run command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_run_missing_part() {
+        assert!(parse_command(args("run --day 4 input.txt")).is_err());
+    }
+
+    #[test]
+    fn test_parse_all() {
+        match parse_command(args("all")) {
+            Ok(Command::All { inputs }) => assert_eq!(inputs.to_str(), Some("inputs")),
+            _ => panic!("expected the all command"),
+        }
+        match parse_command(args("all --inputs data")) {
+            Ok(Command::All { inputs }) => assert_eq!(inputs.to_str(), Some("data")),
+            _ => panic!("expected the all command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        match parse_command(args("verify -i data")) {
+            Ok(Command::Verify { inputs }) => assert_eq!(inputs.to_str(), Some("data")),
+            _ => panic!("expected the verify command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        match parse_command(args("bench --day 6 -n 50")) {
+            Ok(Command::Bench {
+                day,
+                iterations,
+                path,
+            }) => {
+                assert_eq!(day, 6);
+                assert_eq!(iterations, 50);
+                assert_eq!(path, None);
+            }
+            _ => panic!("expected the bench command"),
+        }
+        match parse_command(args("bench --day 6")) {
+            Ok(Command::Bench { iterations, .. }) => assert_eq!(iterations, 100),
+            _ => panic!("expected the bench command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_stats() {
+        match parse_command(args("stats -")) {
+            Ok(Command::Stats { path }) => assert_eq!(path, Some(PathBuf::from("-"))),
+            _ => panic!("expected the stats command"),
+        }
+        assert!(parse_command(args("stats a.txt b.txt")).is_err());
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let parsed = parse_args(args("run --day 4 --format json --part 2")).unwrap();
+        assert_eq!(parsed.format, Format::Json);
+        assert!(matches!(
+            parsed.command,
+            Command::Run {
+                day: 4,
+                part: 2,
+                ..
+            }
+        ));
+        let parsed = parse_args(args("all")).unwrap();
+        assert_eq!(parsed.format, Format::Text);
+        assert!(parse_args(args("all --format yaml")).is_err());
+    }
+
+    #[test]
+    fn test_command_target() {
+        let target = |line| parse_command(args(line)).unwrap().target();
+        assert_eq!(target("run -d 4 -p 2"), (Some(4), Some(2)));
+        assert_eq!(target("bench -d 6"), (Some(6), None));
+        assert_eq!(target("stats"), (Some(1), None));
+        assert_eq!(target("all"), (None, None));
+    }
+
+    #[test]
+    fn test_parse_unknown_command() {
+        assert!(parse_command(args("walk --day 4")).is_err());
+    }
+}
@@ -1,58 +1,618 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::io::BufRead;
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+const DAY: u8 = 1;
+
+const OVERFLOW: &str = "calorie total overflows";
+
+/// A calorie count or total. Generated inputs can exceed `u32`, and every sum is checked.
+pub type Calories = u64;
+
+fn checked_sum<I: IntoIterator<Item = Calories>>(values: I) -> Result<Calories> {
+    values
+        .into_iter()
+        .try_fold(0, Calories::checked_add)
+        .ok_or(Error::solve(DAY, OVERFLOW))
+}
+
 #[derive(Eq, PartialEq)]
-struct Elf {
-    pub calories: Vec<u32>,
+pub struct Elf {
+    pub calories: Vec<Calories>,
 }
 
 impl Elf {
-    pub fn total_calories(&self) -> u32 {
-        self.calories.iter().sum()
+    /// Fails instead of wrapping when the total does not fit in [`Calories`].
+    pub fn total_calories(&self) -> Result<Calories> {
+        checked_sum(self.calories.iter().copied())
+    }
+
+    /// The exact total even when [`Elf::total_calories`] overflows, so ordering cannot fail.
+    fn wide_total(&self) -> u128 {
+        self.calories.iter().map(|&calories| calories as u128).sum()
     }
 }
 
 impl std::cmp::Ord for Elf {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.total_calories().cmp(&other.total_calories())
+        self.wide_total().cmp(&other.wide_total())
     }
 }
 
 impl std::cmp::PartialOrd for Elf {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.total_calories().cmp(&other.total_calories()))
+        Some(self.cmp(other))
     }
 }
 
-fn parse_elf_calories(list: &str) -> Vec<Elf> {
-    list.split("\n\n")
-        .map(|calblock| {
-            let calories = calblock
-                .split("\n")
-                .map(|calorie| calorie.parse().expect("could not parse string to number"))
-                .collect();
-            Elf { calories }
-        })
-        .collect()
+pub fn parse_elf_calories(list: &str) -> Result<Vec<Elf>> {
+    let mut elves = vec![];
+    for calblock in crate::util::blocks(list) {
+        let calories = calblock
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(idx, calorie)| {
+                calorie.parse().map_err(|_| {
+                    Error::parse(DAY, calorie, 1, "invalid calorie count")
+                        .offset_lines(calblock.line - 1 + idx)
+                })
+            })
+            .collect::<Result<Vec<Calories>>>()?;
+        elves.push(Elf { calories });
+    }
+
+    Ok(elves)
 }
 
-pub fn most_calories(list: &str) -> u32 {
-    parse_elf_calories(list)
+pub fn most_calories(elves: &[Elf]) -> Result<Calories> {
+    elves
         .iter()
-        .max_by(|elf1, elf2| elf1.total_calories().cmp(&elf2.total_calories()))
-        .expect("No elves in list")
+        .max()
+        .ok_or(Error::solve(DAY, "no elves in list"))?
         .total_calories()
 }
 
-pub fn total_top_three_calories(list: &str) -> u32 {
-    let mut elves = parse_elf_calories(list);
-    elves.sort();
-    let mut top_elves: Vec<Elf> = vec![];
-    for _ in 0..3 {
-        top_elves.push(elves.pop().expect("Not enough elves"))
+/// An elf's position in the list, counting from zero, and its total calories.
+#[derive(Debug, PartialEq)]
+pub struct RankedElf {
+    pub index: usize,
+    pub total: Calories,
+}
+
+/// Keeps the `n` elves with the most calories seen so far in a min-heap, so memory is bounded by
+/// `n` rather than by the number of elves. Ties go to the elf that came first.
+struct TopN {
+    n: usize,
+    heap: BinaryHeap<Reverse<(Calories, Reverse<usize>)>>,
+}
+
+impl TopN {
+    fn new(n: usize) -> TopN {
+        TopN {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    fn push(&mut self, index: usize, total: Calories) {
+        self.heap.push(Reverse((total, Reverse(index))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// The kept elves, most calories first.
+    fn into_ranked(self) -> Vec<RankedElf> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| RankedElf { index, total })
+            .collect()
+    }
+}
+
+/// Finds the `n` elves carrying the most calories in a single pass over `reader`, without
+/// keeping the whole list in memory. Returns fewer than `n` elves if the list is shorter. Each
+/// line is normalized with the day's policy, so `reader` may hold the raw input file.
+pub fn top_n<R: BufRead>(reader: R, n: usize) -> Result<Vec<RankedElf>> {
+    let mut top = TopN::new(n);
+    let mut index = 0;
+    let mut total: Option<Calories> = None;
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = crate::util::normalize_line(&line, idx, &CalorieCounting::NORMALIZATION);
+        // Blank lines are treated the same way as `util::blocks` treats them.
+        if line.trim().is_empty() {
+            if let Some(total) = total.take() {
+                top.push(index, total);
+                index += 1;
+            }
+            continue;
+        }
+        let calories: Calories = line
+            .parse()
+            .map_err(|_| Error::parse(DAY, line, 1, "invalid calorie count").offset_lines(idx))?;
+        total = Some(
+            total
+                .unwrap_or(0)
+                .checked_add(calories)
+                .ok_or(Error::solve(DAY, OVERFLOW))?,
+        );
+    }
+    if let Some(total) = total {
+        top.push(index, total);
+    }
+
+    Ok(top.into_ranked())
+}
+
+pub fn total_top_three_calories(elves: &[Elf]) -> Result<Calories> {
+    let mut top = TopN::new(3);
+    for (index, elf) in elves.iter().enumerate() {
+        top.push(index, elf.total_calories()?);
+    }
+    let top_elves = top.into_ranked();
+    if top_elves.len() < 3 {
+        return Err(Error::solve(DAY, "fewer than three elves in list"));
+    }
+    checked_sum(top_elves.iter().map(|elf| elf.total))
+}
+
+/// A calorie total and every elf tied on it, in list order.
+#[derive(Debug, PartialEq)]
+pub struct Standing {
+    pub total: Calories,
+    pub elves: Vec<usize>,
+}
+
+/// Tracks elves as their calorie entries arrive, so the leaders can be queried at any time
+/// without re-reading the list. The elf that is still receiving entries is included in every
+/// query with the calories it has so far.
+#[derive(Default)]
+pub struct Leaderboard {
+    elves: Vec<Elf>,
+    totals: Vec<Calories>,
+    by_total: BTreeMap<Calories, BTreeSet<usize>>,
+    open: bool,
+}
+
+impl Leaderboard {
+    pub fn new() -> Leaderboard {
+        Leaderboard::default()
+    }
+
+    fn set_total(&mut self, index: usize, total: Calories) {
+        if let Some(&old) = self.totals.get(index) {
+            let tied = self.by_total.get_mut(&old).expect("total is tracked");
+            tied.remove(&index);
+            if tied.is_empty() {
+                self.by_total.remove(&old);
+            }
+            self.totals[index] = total;
+        } else {
+            self.totals.push(total);
+        }
+        self.by_total.entry(total).or_default().insert(index);
+    }
+
+    /// Adds an item to the current elf, starting a new elf after a boundary. An item that would
+    /// overflow the elf's total is rejected and leaves the leaderboard unchanged.
+    pub fn add_calories(&mut self, calories: Calories) -> Result<()> {
+        let current = if self.open {
+            self.totals[self.elves.len() - 1]
+        } else {
+            0
+        };
+        let total = current
+            .checked_add(calories)
+            .ok_or(Error::solve(DAY, OVERFLOW))?;
+        if !self.open {
+            self.elves.push(Elf { calories: vec![] });
+            self.open = true;
+        }
+        let index = self.elves.len() - 1;
+        self.elves[index].calories.push(calories);
+        self.set_total(index, total);
+        Ok(())
+    }
+
+    /// Marks the end of the current elf. Consecutive boundaries do not create empty elves.
+    pub fn end_elf(&mut self) {
+        self.open = false;
+    }
+
+    /// Adds a whole elf at once, after ending the current one.
+    pub fn push_elf(&mut self, elf: Elf) -> Result<()> {
+        let total = elf.total_calories()?;
+        self.end_elf();
+        let index = self.elves.len();
+        self.set_total(index, total);
+        self.elves.push(elf);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.elves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elves.is_empty()
+    }
+
+    pub fn elf(&self, index: usize) -> Option<&Elf> {
+        self.elves.get(index)
+    }
+
+    /// The largest total and every elf carrying it.
+    pub fn max(&self) -> Option<Standing> {
+        self.top(1).pop()
+    }
+
+    /// The standings that cover the first `k` places. An elf tied with the `k`th place is always
+    /// included, so the standings may name more than `k` elves.
+    pub fn top(&self, k: usize) -> Vec<Standing> {
+        let mut standings = vec![];
+        let mut covered = 0;
+        for (&total, tied) in self.by_total.iter().rev() {
+            if covered >= k {
+                break;
+            }
+            covered += tied.len();
+            standings.push(Standing {
+                total,
+                elves: tied.iter().copied().collect(),
+            });
+        }
+
+        standings
+    }
+
+    /// The sum of the `k` largest totals, or of every total if there are fewer than `k` elves.
+    pub fn top_k_sum(&self, k: usize) -> Result<Calories> {
+        let mut remaining = k;
+        let mut sum: Calories = 0;
+        for (&total, tied) in self.by_total.iter().rev() {
+            if remaining == 0 {
+                break;
+            }
+            let taken = remaining.min(tied.len());
+            sum = total
+                .checked_mul(taken as Calories)
+                .and_then(|tied_sum| sum.checked_add(tied_sum))
+                .ok_or(Error::solve(DAY, OVERFLOW))?;
+            remaining -= taken;
+        }
+
+        Ok(sum)
+    }
+}
+
+/// How [`plan_loads`] searches for a balanced assignment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlanMode {
+    /// Gives each item, largest first, to the elf carrying the least so far. Fast, and never more
+    /// than a third above the optimum.
+    Greedy,
+    /// Searches every assignment, pruning with the greedy result. Only for small inputs.
+    Exact,
+}
+
+/// The most items [`PlanMode::Exact`] will try to assign. Past this the search time grows
+/// quickly; at it, the search finishes within about a tenth of a second in a release build.
+pub const EXACT_PLAN_MAX_ITEMS: usize = 20;
+
+/// The most search steps [`PlanMode::Exact`] takes before giving up, which bounds its running
+/// time even on inputs where the pruning does not help.
+pub const EXACT_PLAN_NODE_BUDGET: usize = 5_000_000;
+
+/// Calorie items redistributed across the same number of elves.
+pub struct LoadPlan {
+    pub elves: Vec<Elf>,
+    pub max_total: Calories,
+    /// No assignment can have a smaller maximum: the larger of the largest item and the average
+    /// load rounded up.
+    pub lower_bound: Calories,
+}
+
+impl LoadPlan {
+    /// How far the plan is from the lower bound. Zero means the plan is optimal.
+    pub fn gap(&self) -> Calories {
+        self.max_total - self.lower_bound
+    }
+}
+
+/// Branch-and-bound search for [`PlanMode::Exact`]. Items are placed largest first, and a
+/// branch is dropped as soon as it provably cannot beat the best plan found so far.
+struct LoadSearch<'a> {
+    items: &'a [Calories],
+    /// `remaining[i]` is the sum of `items[i..]`.
+    remaining: Vec<Calories>,
+    totals: Vec<Calories>,
+    assignment: Vec<usize>,
+    best: Calories,
+    best_assignment: Vec<usize>,
+    lower_bound: Calories,
+    nodes: usize,
+}
+
+impl LoadSearch<'_> {
+    /// Returns `false` if the node budget ran out before the search finished.
+    fn search(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes > EXACT_PLAN_NODE_BUDGET {
+            return false;
+        }
+        if self.best == self.lower_bound {
+            return true;
+        }
+        let next = self.assignment.len();
+        let Some(&item) = self.items.get(next) else {
+            // Every placement was below the best maximum, so this assignment beats it.
+            self.best = self.totals.iter().copied().max().unwrap_or(0);
+            self.best_assignment = self.assignment.clone();
+            return true;
+        };
+
+        // Remaining-work bound: the fullest elf, or the average load once everything is placed.
+        let remaining = self.remaining[next];
+        let placed: Calories = self.totals.iter().sum();
+        let current_max = self.totals.iter().copied().max().unwrap_or(0);
+        let average = (placed + remaining).div_ceil(self.totals.len() as Calories);
+        if current_max.max(average) >= self.best {
+            return true;
+        }
+        // Every elf is below `best`. Room smaller than the smallest item left can never be
+        // filled, so if the usable room cannot hold the remaining items, no plan here is better.
+        let smallest = *self.items.last().expect("there is an item left");
+        let usable = self
+            .totals
+            .iter()
+            .map(|&total| self.best - 1 - total)
+            .filter(|&room| room >= smallest)
+            .fold(0, Calories::saturating_add);
+        if usable < remaining {
+            return true;
+        }
+
+        for bin in 0..self.totals.len() {
+            // Elves with the same load are interchangeable, so only try the first of them.
+            let total = self.totals[bin];
+            if self.totals[..bin].contains(&total) || total + item >= self.best {
+                continue;
+            }
+            self.totals[bin] += item;
+            self.assignment.push(bin);
+            let finished = self.search();
+            self.assignment.pop();
+            self.totals[bin] -= item;
+            if !finished {
+                return false;
+            }
+        }
+
+        true
     }
-    top_elves.iter().map(|elf| elf.total_calories()).sum()
 }
 
+/// Redistributes every calorie item across the same number of elves, minimizing the largest
+/// total any elf carries.
+pub fn plan_loads(elves: &[Elf], mode: PlanMode) -> Result<LoadPlan> {
+    if elves.is_empty() {
+        return Err(Error::solve(DAY, "no elves in list"));
+    }
+    let mut items: Vec<Calories> = elves
+        .iter()
+        .flat_map(|elf| elf.calories.iter().copied())
+        .collect();
+    if mode == PlanMode::Exact && items.len() > EXACT_PLAN_MAX_ITEMS {
+        return Err(Error::solve(
+            DAY,
+            format!(
+                "{} items is too many to plan exactly (at most {EXACT_PLAN_MAX_ITEMS})",
+                items.len()
+            ),
+        ));
+    }
+    items.sort_unstable_by(|a, b| b.cmp(a));
+
+    // Every elf's load is at most the overall total, so once it fits nothing below can overflow.
+    let total = checked_sum(items.iter().copied())?;
+    let lower_bound = total
+        .div_ceil(elves.len() as Calories)
+        .max(items.first().copied().unwrap_or(0));
+    let max_total = |plan: &[Elf]| -> Result<Calories> {
+        Ok(plan
+            .iter()
+            .map(Elf::total_calories)
+            .collect::<Result<Vec<Calories>>>()?
+            .into_iter()
+            .max()
+            .unwrap_or(0))
+    };
+
+    // Running totals, lightest first; ties go to the lowest index.
+    let mut plan: Vec<Elf> = elves.iter().map(|_| Elf { calories: vec![] }).collect();
+    let mut lightest: BinaryHeap<Reverse<(Calories, usize)>> =
+        (0..plan.len()).map(|index| Reverse((0, index))).collect();
+    for &item in &items {
+        let Reverse((load, index)) = lightest.pop().expect("there is at least one elf");
+        plan[index].calories.push(item);
+        lightest.push(Reverse((load + item, index)));
+    }
+
+    if mode == PlanMode::Exact {
+        let mut remaining = vec![0; items.len() + 1];
+        for idx in (0..items.len()).rev() {
+            remaining[idx] = remaining[idx + 1] + items[idx];
+        }
+        let mut search = LoadSearch {
+            items: &items,
+            remaining,
+            totals: vec![0; elves.len()],
+            assignment: vec![],
+            best: max_total(&plan)?,
+            best_assignment: vec![],
+            lower_bound,
+            nodes: 0,
+        };
+        if !search.search() {
+            return Err(Error::solve(
+                DAY,
+                format!(
+                    "exact plan gave up after {EXACT_PLAN_NODE_BUDGET} search steps; use the \
+                     greedy mode"
+                ),
+            ));
+        }
+        if !search.best_assignment.is_empty() {
+            plan.iter_mut().for_each(|elf| elf.calories.clear());
+            for (&item, &bin) in items.iter().zip(&search.best_assignment) {
+                plan[bin].calories.push(item);
+            }
+        }
+    }
+
+    Ok(LoadPlan {
+        max_total: max_total(&plan)?,
+        elves: plan,
+        lower_bound,
+    })
+}
+
+/// The most buckets [`CalorieStats`] splits the totals into.
+pub const HISTOGRAM_BUCKETS: usize = 10;
+
+/// The number of elves whose totals fall in `low..=high`.
+#[derive(Debug, PartialEq)]
+pub struct Bucket {
+    pub low: Calories,
+    pub high: Calories,
+    pub count: usize,
+}
+
+/// Summary statistics of an elf calorie list, for sanity-checking inputs. Elves are identified by
+/// their position in the list, counting from zero.
+#[derive(Debug, PartialEq)]
+pub struct CalorieStats {
+    pub elves: usize,
+    pub mean: f64,
+    pub median: f64,
+    /// The population standard deviation of the totals.
+    pub std_dev: f64,
+    pub largest_item: Calories,
+    pub most_items: usize,
+    /// Every elf carrying `most_items` items.
+    pub most_items_elves: Vec<usize>,
+    pub histogram: Vec<Bucket>,
+}
+
+impl CalorieStats {
+    pub fn new(elves: &[Elf]) -> Result<CalorieStats> {
+        if elves.is_empty() {
+            return Err(Error::solve(DAY, "no elves in list"));
+        }
+
+        let mut totals = elves
+            .iter()
+            .map(Elf::total_calories)
+            .collect::<Result<Vec<Calories>>>()?;
+        totals.sort_unstable();
+        let count = totals.len();
+        let mean = totals.iter().map(|&total| total as f64).sum::<f64>() / count as f64;
+        let median = if count.is_multiple_of(2) {
+            (totals[count / 2 - 1] as f64 + totals[count / 2] as f64) / 2.0
+        } else {
+            totals[count / 2] as f64
+        };
+        let variance = totals
+            .iter()
+            .map(|&total| (total as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count as f64;
+        let most_items = elves
+            .iter()
+            .map(|elf| elf.calories.len())
+            .max()
+            .unwrap_or(0);
+
+        Ok(CalorieStats {
+            elves: count,
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            largest_item: elves
+                .iter()
+                .flat_map(|elf| elf.calories.iter().copied())
+                .max()
+                .unwrap_or(0),
+            most_items,
+            most_items_elves: elves
+                .iter()
+                .enumerate()
+                .filter(|(_, elf)| elf.calories.len() == most_items)
+                .map(|(index, _)| index)
+                .collect(),
+            histogram: histogram(&totals, HISTOGRAM_BUCKETS),
+        })
+    }
+}
+
+/// Splits sorted, non-empty `totals` into at most `buckets` equally wide buckets spanning the
+/// smallest to the largest total.
+fn histogram(totals: &[Calories], buckets: usize) -> Vec<Bucket> {
+    // Bucket edges are computed in u128 so a span of the whole `Calories` range cannot overflow.
+    let (min, max) = (totals[0] as u128, totals[totals.len() - 1] as u128);
+    let buckets = buckets.min(totals.len()) as u128;
+    let width = (max - min + 1).div_ceil(buckets);
+    let mut histogram: Vec<Bucket> = (0..buckets)
+        .map(|idx| min + idx * width)
+        .take_while(|&low| low <= max)
+        .map(|low| Bucket {
+            low: low as Calories,
+            high: (low + width - 1).min(max) as Calories,
+            count: 0,
+        })
+        .collect();
+    for &total in totals {
+        histogram[((total as u128 - min) / width) as usize].count += 1;
+    }
+
+    histogram
+}
+
+pub struct CalorieCounting;
+
+impl Solution for CalorieCounting {
+    const DAY: u8 = DAY;
+
+    type Model = Vec<Elf>;
+    type Answer = Calories;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        parse_elf_calories(input)
+    }
+
+    fn part1(model: &Self::Model) -> Result<Self::Answer> {
+        most_calories(model)
+    }
+
+    fn part2(model: &Self::Model) -> Result<Self::Answer> {
+        total_top_three_calories(model)
+    }
+}
+
+#[cfg(test)]
 mod tests {
-    use super::{most_calories, parse_elf_calories, total_top_three_calories};
+    use super::{
+        parse_elf_calories, top_n, Bucket, CalorieCounting, CalorieStats, Elf, Leaderboard,
+        PlanMode, RankedElf, Standing,
+    };
+    use crate::error::Error;
+    use crate::solution::Solution;
     const EXAMPLE_LIST: &str = "1000
 2000
 3000
@@ -70,27 +630,331 @@ mod tests {
 
     #[test]
     fn test_parse_elf_calories() {
-        let elves = parse_elf_calories(EXAMPLE_LIST);
+        let elves = parse_elf_calories(EXAMPLE_LIST).unwrap();
         assert_eq!(elves.len(), 5)
     }
 
+    #[test]
+    fn test_parse_elf_calories_invalid() {
+        let err = parse_elf_calories("1000\n\n2000\n3x00").err();
+        assert!(matches!(
+            err,
+            Some(Error::Parse {
+                day: 1,
+                line: 4,
+                column: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_elf_calories_tolerant_blocks() {
+        let list = "\r\n1000\r\n2000\r\n \r\n\r\n3000\r\n\r\n";
+        let elves = parse_elf_calories(list).unwrap();
+        assert_eq!(elves.len(), 2);
+        assert_eq!(elves[0].calories, vec![1_000, 2_000]);
+        assert_eq!(elves[1].calories, vec![3_000]);
+        assert_eq!(top_n(list.as_bytes(), 2).unwrap().len(), 2);
+        let err = parse_elf_calories("1000\n\n\n  \n2x00").err();
+        assert!(matches!(
+            err,
+            Some(Error::Parse {
+                day: 1,
+                line: 5,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_elf_total_calories() {
-        let elves = parse_elf_calories(EXAMPLE_LIST);
-        assert_eq!(elves[0].total_calories(), 6_000);
-        assert_eq!(elves[1].total_calories(), 4_000);
-        assert_eq!(elves[2].total_calories(), 11_000);
-        assert_eq!(elves[3].total_calories(), 24_000);
-        assert_eq!(elves[4].total_calories(), 10_000);
+        let elves = parse_elf_calories(EXAMPLE_LIST).unwrap();
+        assert_eq!(elves[0].total_calories().unwrap(), 6_000);
+        assert_eq!(elves[1].total_calories().unwrap(), 4_000);
+        assert_eq!(elves[2].total_calories().unwrap(), 11_000);
+        assert_eq!(elves[3].total_calories().unwrap(), 24_000);
+        assert_eq!(elves[4].total_calories().unwrap(), 10_000);
+    }
+
+    #[test]
+    fn test_calorie_overflow() {
+        let list = format!("{}\n1\n\n5", u64::MAX);
+        let elves = CalorieCounting::parse(&list).unwrap();
+        assert!(elves[0].total_calories().is_err());
+        // Ordering still sees the true totals.
+        assert!(elves[0] > elves[1]);
+        assert!(matches!(
+            CalorieCounting::part1(&elves),
+            Err(Error::Solve { day: 1, .. })
+        ));
+        // Every overflow is reported the same way, wherever it is found.
+        assert!(matches!(
+            top_n(list.as_bytes(), 1),
+            Err(Error::Solve { day: 1, .. })
+        ));
+        assert!(matches!(
+            super::plan_loads(&elves, PlanMode::Greedy),
+            Err(Error::Solve { day: 1, .. })
+        ));
+
+        let mut leaderboard = Leaderboard::new();
+        leaderboard.add_calories(u64::MAX).unwrap();
+        assert!(matches!(
+            leaderboard.add_calories(1),
+            Err(Error::Solve { day: 1, .. })
+        ));
+        assert_eq!(leaderboard.elf(0).unwrap().calories, vec![u64::MAX]);
+        leaderboard.end_elf();
+        leaderboard.add_calories(1).unwrap();
+        assert!(matches!(
+            leaderboard.top_k_sum(2),
+            Err(Error::Solve { day: 1, .. })
+        ));
+        assert_eq!(leaderboard.top_k_sum(1).unwrap(), u64::MAX);
     }
 
     #[test]
     fn test_most_calories() {
-        assert_eq!(most_calories(EXAMPLE_LIST), 24_000)
+        let elves = CalorieCounting::parse(EXAMPLE_LIST).unwrap();
+        assert_eq!(CalorieCounting::part1(&elves).unwrap(), 24_000)
     }
 
     #[test]
     fn test_total_top_three_calories() {
-        assert_eq!(total_top_three_calories(EXAMPLE_LIST), 45_000)
+        let elves = CalorieCounting::parse(EXAMPLE_LIST).unwrap();
+        assert_eq!(CalorieCounting::part2(&elves).unwrap(), 45_000)
+    }
+
+    #[test]
+    fn test_top_n() {
+        let top = top_n(EXAMPLE_LIST.as_bytes(), 3).unwrap();
+        assert_eq!(
+            top,
+            vec![
+                RankedElf {
+                    index: 3,
+                    total: 24_000
+                },
+                RankedElf {
+                    index: 2,
+                    total: 11_000
+                },
+                RankedElf {
+                    index: 4,
+                    total: 10_000
+                },
+            ]
+        );
+        assert_eq!(top_n(EXAMPLE_LIST.as_bytes(), 10).unwrap().len(), 5);
+        assert!(top_n(EXAMPLE_LIST.as_bytes(), 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_top_n_ties_and_errors() {
+        let top = top_n("5\n\n3\n2\n\n1".as_bytes(), 1).unwrap();
+        assert_eq!(top, vec![RankedElf { index: 0, total: 5 }]);
+        let err = top_n("1000\n\n2000\n3x00".as_bytes(), 2).err();
+        assert!(matches!(
+            err,
+            Some(Error::Parse {
+                day: 1,
+                line: 4,
+                column: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_top_n_normalizes_lines() {
+        let raw = "\u{feff}1000\r\n2000 \r\n\r\n5000\t\r\n \r\n";
+        let top = top_n(raw.as_bytes(), 2).unwrap();
+        assert_eq!(
+            top[0],
+            RankedElf {
+                index: 1,
+                total: 5000
+            }
+        );
+        assert_eq!(
+            top[1],
+            RankedElf {
+                index: 0,
+                total: 3000
+            }
+        );
+        let normalized = crate::util::normalize(raw, &CalorieCounting::NORMALIZATION);
+        assert_eq!(CalorieCounting::parse(&normalized).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        let mut leaderboard = Leaderboard::new();
+        assert_eq!(leaderboard.max(), None);
+        for line in EXAMPLE_LIST.lines() {
+            match line {
+                "" => leaderboard.end_elf(),
+                calories => leaderboard.add_calories(calories.parse().unwrap()).unwrap(),
+            }
+        }
+        assert_eq!(leaderboard.len(), 5);
+        assert_eq!(
+            leaderboard.max(),
+            Some(Standing {
+                total: 24_000,
+                elves: vec![3]
+            })
+        );
+        assert_eq!(leaderboard.top_k_sum(3).unwrap(), 45_000);
+        assert_eq!(leaderboard.top_k_sum(10).unwrap(), 55_000);
+
+        // The open elf moves up as it receives more items.
+        leaderboard.add_calories(14_000).unwrap();
+        assert_eq!(leaderboard.len(), 5);
+        assert_eq!(leaderboard.elf(4).unwrap().calories, vec![10_000, 14_000]);
+        assert_eq!(leaderboard.max().unwrap().elves, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_leaderboard_ties() {
+        let mut leaderboard = Leaderboard::new();
+        for calories in [vec![5], vec![7], vec![2, 3], vec![7], vec![1]] {
+            leaderboard.push_elf(Elf { calories }).unwrap();
+        }
+        assert_eq!(
+            leaderboard.top(2),
+            vec![Standing {
+                total: 7,
+                elves: vec![1, 3]
+            }]
+        );
+        assert_eq!(
+            leaderboard.top(3),
+            vec![
+                Standing {
+                    total: 7,
+                    elves: vec![1, 3]
+                },
+                Standing {
+                    total: 5,
+                    elves: vec![0, 2]
+                },
+            ]
+        );
+        assert_eq!(leaderboard.top_k_sum(3).unwrap(), 19);
+        assert!(leaderboard.top(0).is_empty());
+    }
+
+    #[test]
+    fn test_plan_loads() {
+        let elves = parse_elf_calories(EXAMPLE_LIST).unwrap();
+        for mode in [PlanMode::Greedy, PlanMode::Exact] {
+            let plan = super::plan_loads(&elves, mode).unwrap();
+            assert_eq!(plan.elves.len(), 5);
+            assert_eq!(plan.max_total, 11_000);
+            assert_eq!(plan.lower_bound, 11_000);
+            assert_eq!(plan.gap(), 0);
+        }
+    }
+
+    #[test]
+    fn test_plan_loads_exact_beats_greedy() {
+        let elves = parse_elf_calories("3\n3\n2\n\n2\n2").unwrap();
+        let greedy = super::plan_loads(&elves, PlanMode::Greedy).unwrap();
+        assert_eq!(greedy.max_total, 7);
+        assert_eq!(greedy.gap(), 1);
+        let exact = super::plan_loads(&elves, PlanMode::Exact).unwrap();
+        assert_eq!(exact.max_total, 6);
+        assert_eq!(exact.gap(), 0);
+        let mut totals: Vec<u64> = exact
+            .elves
+            .iter()
+            .map(|elf| elf.total_calories().unwrap())
+            .collect();
+        totals.sort();
+        assert_eq!(totals, vec![6, 6]);
+    }
+
+    #[test]
+    fn test_plan_loads_limits() {
+        assert!(super::plan_loads(&[], PlanMode::Greedy).is_err());
+        let many = Elf {
+            calories: vec![1; super::EXACT_PLAN_MAX_ITEMS + 1],
+        };
+        assert!(super::plan_loads(&[many], PlanMode::Exact).is_err());
+    }
+
+    // Takes seconds in a debug build; run with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_plan_loads_exact_worst_case() {
+        // The slowest of a few hundred random inputs at the item cap.
+        let items = [
+            3923, 5882, 8175, 9626, 9278, 4703, 4678, 6758, 6195, 5341, 6476, 4684, 7177, 9397,
+            5391, 8765, 7353, 9041, 7740, 1763,
+        ];
+        assert_eq!(items.len(), super::EXACT_PLAN_MAX_ITEMS);
+        let elves: Vec<Elf> = (0..5)
+            .map(|i| Elf {
+                calories: items.iter().copied().skip(i).step_by(5).collect(),
+            })
+            .collect();
+        let greedy = super::plan_loads(&elves, PlanMode::Greedy).unwrap();
+        let exact = super::plan_loads(&elves, PlanMode::Exact).unwrap();
+        let total: super::Calories = items.iter().sum();
+        assert!(exact.max_total <= greedy.max_total);
+        assert!(exact.max_total >= exact.lower_bound);
+        assert_eq!(exact.lower_bound, total.div_ceil(5));
+        let carried: super::Calories = exact.elves.iter().flat_map(|e| &e.calories).sum();
+        assert_eq!(carried, total);
+    }
+
+    #[test]
+    fn test_calorie_stats() {
+        let elves = parse_elf_calories(EXAMPLE_LIST).unwrap();
+        let stats = CalorieStats::new(&elves).unwrap();
+        assert_eq!(stats.elves, 5);
+        assert_eq!(stats.mean, 11_000.0);
+        assert_eq!(stats.median, 10_000.0);
+        assert!((stats.std_dev - 6_985.70).abs() < 0.01);
+        assert_eq!(stats.largest_item, 10_000);
+        assert_eq!(stats.most_items, 3);
+        assert_eq!(stats.most_items_elves, vec![0, 3]);
+        assert_eq!(stats.histogram.len(), 5);
+        assert_eq!(
+            stats.histogram[0],
+            Bucket {
+                low: 4_000,
+                high: 8_000,
+                count: 2
+            }
+        );
+        assert_eq!(
+            stats.histogram.iter().map(|b| b.count).collect::<Vec<_>>(),
+            vec![2, 2, 0, 0, 1]
+        );
+        assert!(CalorieStats::new(&[]).is_err());
+    }
+
+    #[test]
+    fn test_calorie_stats_single_total() {
+        let elves = parse_elf_calories("7\n\n7").unwrap();
+        let stats = CalorieStats::new(&elves).unwrap();
+        assert_eq!(stats.std_dev, 0.0);
+        assert_eq!(
+            stats.histogram,
+            vec![Bucket {
+                low: 7,
+                high: 7,
+                count: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_total_top_three_calories_not_enough_elves() {
+        let elves = CalorieCounting::parse("1000\n\n2000").unwrap();
+        assert!(CalorieCounting::part2(&elves).is_err())
     }
 }
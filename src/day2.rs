@@ -1,7 +1,13 @@
 use std::str::FromStr;
 
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+const DAY: u8 = 2;
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy)]
-enum RPS {
+pub enum RPS {
     Rock = 1,
     Paper = 2,
     Scissors = 3,
@@ -25,55 +31,101 @@ impl RPS {
 }
 
 impl FromStr for RPS {
-    type Err = ();
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         match s {
             "A" => Ok(RPS::Rock),
             "B" => Ok(RPS::Paper),
             "C" => Ok(RPS::Scissors),
-            _ => Err(()),
+            _ => Err(Error::parse(DAY, s, 1, "expected A, B or C")),
+        }
+    }
+}
+
+/// The second column of the strategy guide, before it is interpreted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Response {
+    X,
+    Y,
+    Z,
+}
+
+impl FromStr for Response {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "X" => Ok(Response::X),
+            "Y" => Ok(Response::Y),
+            "Z" => Ok(Response::Z),
+            _ => Err(Error::parse(DAY, s, 1, "expected X, Y or Z")),
+        }
+    }
+}
+
+/// How to read the response column of the strategy guide.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpretation {
+    /// X, Y and Z are the moves to play: Rock, Paper and Scissors.
+    Move,
+    /// X, Y and Z are the desired outcome: lose, draw and win.
+    Outcome,
+}
+
+impl From<Response> for RPS {
+    fn from(response: Response) -> Self {
+        match response {
+            Response::X => RPS::Rock,
+            Response::Y => RPS::Paper,
+            Response::Z => RPS::Scissors,
         }
     }
 }
 
 impl RPS {
-    pub fn determine_move(opponent: RPS, value: &str) -> Self {
-        match value {
+    pub fn determine_move(opponent: RPS, outcome: Response) -> Self {
+        match outcome {
             // Lose
-            "X" => opponent.beats(),
+            Response::X => opponent.beats(),
             // Draw
-            "Y" => opponent,
+            Response::Y => opponent,
             // Win
-            "Z" => opponent.beaten_by(),
-            _ => panic!("Invalid instruction"),
+            Response::Z => opponent.beaten_by(),
+        }
+    }
+
+    pub fn respond(opponent: RPS, response: Response, interpretation: Interpretation) -> Self {
+        match interpretation {
+            Interpretation::Move => response.into(),
+            Interpretation::Outcome => RPS::determine_move(opponent, response),
         }
     }
 }
 
 #[derive(Clone, Copy)]
-enum OutcomeScore {
-    LOSS = 0,
-    DRAW = 3,
-    WIN = 6,
+pub enum OutcomeScore {
+    Loss = 0,
+    Draw = 3,
+    Win = 6,
 }
 
-struct Strategy {
-    initial: RPS,
-    response: RPS,
+pub struct Strategy {
+    pub initial: RPS,
+    pub response: RPS,
 }
 
 impl Strategy {
-    fn outcome(&self) -> &OutcomeScore {
+    pub fn outcome(&self) -> &OutcomeScore {
         match (self.initial, self.response) {
             (RPS::Rock, RPS::Rock) | (RPS::Paper, RPS::Paper) | (RPS::Scissors, RPS::Scissors) => {
-                &OutcomeScore::DRAW
+                &OutcomeScore::Draw
             }
             (RPS::Rock, RPS::Paper) | (RPS::Paper, RPS::Scissors) | (RPS::Scissors, RPS::Rock) => {
-                &OutcomeScore::WIN
+                &OutcomeScore::Win
             }
             (RPS::Rock, RPS::Scissors) | (RPS::Paper, RPS::Rock) | (RPS::Scissors, RPS::Paper) => {
-                &OutcomeScore::LOSS
+                &OutcomeScore::Loss
             }
         }
     }
@@ -82,29 +134,109 @@ impl Strategy {
     }
 }
 
-fn parse_strategy_guide(guide_lines: Vec<String>) -> impl Iterator<Item = Strategy> {
-    guide_lines.into_iter().map(|line| {
-        let items: Vec<&str> = line.split(" ").collect();
-        let initial: RPS = items[0].parse().expect("Could not parse first item");
-        let response = RPS::determine_move(initial, items[1]);
-        Strategy { initial, response }
-    })
+/// One line of the strategy guide, with the response column not yet interpreted.
+#[derive(Clone, Copy)]
+pub struct Round {
+    pub opponent: RPS,
+    pub response: Response,
+}
+
+impl Round {
+    pub fn strategy(&self, interpretation: Interpretation) -> Strategy {
+        Strategy {
+            initial: self.opponent,
+            response: RPS::respond(self.opponent, self.response, interpretation),
+        }
+    }
+}
+
+pub fn parse_round(line: &str) -> Result<Round> {
+    let (first, second) = line.split_once(" ").ok_or(Error::parse(
+        DAY,
+        line,
+        line.chars().count() + 1,
+        "expected two columns separated by a space",
+    ))?;
+    let opponent: RPS = first.parse().map_err(|err: Error| err.within(line, 0))?;
+    let response: Response = second
+        .parse()
+        .map_err(|err: Error| err.within(line, first.len() + 1))?;
+    Ok(Round { opponent, response })
+}
+
+pub fn parse_strategy_guide(guide_lines: Vec<String>) -> impl Iterator<Item = Result<Round>> {
+    guide_lines
+        .into_iter()
+        .enumerate()
+        .map(|(idx, line)| parse_round(&line).map_err(|err| err.offset_lines(idx)))
 }
 
-pub fn strategy_guide_total_score(guide_lines: Vec<String>) -> u32 {
-    parse_strategy_guide(guide_lines)
-        .map(|strategy| strategy.score())
+pub fn strategy_guide_total_score(guide: &[Round], interpretation: Interpretation) -> u32 {
+    guide
+        .iter()
+        .map(|round| round.strategy(interpretation).score())
         .sum()
 }
 
+pub struct RockPaperScissors;
+
+impl Solution for RockPaperScissors {
+    const DAY: u8 = DAY;
+
+    type Model = Vec<Round>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        parse_strategy_guide(crate::util::lines(input)).collect()
+    }
+
+    fn part1(model: &Self::Model) -> Result<Self::Answer> {
+        Ok(strategy_guide_total_score(model, Interpretation::Move))
+    }
+
+    fn part2(model: &Self::Model) -> Result<Self::Answer> {
+        Ok(strategy_guide_total_score(model, Interpretation::Outcome))
+    }
+}
+
+#[cfg(test)]
 mod tests {
+    use crate::error::Error;
+    use crate::solution::Solution;
+
     const STRATEGY_GUIDE: &str = "A Y
 B X
 C Z";
 
+    #[test]
+    fn test_strategy_guide_total_score_moves() {
+        let guide = super::RockPaperScissors::parse(STRATEGY_GUIDE).unwrap();
+        assert_eq!(super::RockPaperScissors::part1(&guide).unwrap(), 15)
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!("Z".parse::<super::Response>().unwrap(), super::Response::Z);
+        assert!("C".parse::<super::Response>().is_err());
+    }
+
     #[test]
     fn test_strategy_guide_total_score() {
-        let guide_lines = STRATEGY_GUIDE.split("\n").map(|l| l.to_string()).collect();
-        assert_eq!(super::strategy_guide_total_score(guide_lines), 12)
+        let guide = super::RockPaperScissors::parse(STRATEGY_GUIDE).unwrap();
+        assert_eq!(super::RockPaperScissors::part2(&guide).unwrap(), 12)
+    }
+
+    #[test]
+    fn test_parse_strategy_guide_invalid() {
+        let err = super::RockPaperScissors::parse("A Y\nB W").err();
+        assert!(matches!(
+            err,
+            Some(Error::Parse {
+                day: 2,
+                line: 2,
+                column: 3,
+                ..
+            })
+        ));
     }
 }
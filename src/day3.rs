@@ -1,7 +1,12 @@
 use std::collections::HashSet;
 
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+const DAY: u8 = 3;
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Item(char);
+pub struct Item(pub char);
 
 impl Item {
     pub fn priority(&self) -> u32 {
@@ -17,13 +22,13 @@ impl Item {
     }
 }
 
-struct Compartment(Vec<Item>);
+pub struct Compartment(pub Vec<Item>);
 
 impl Compartment {
-    fn to_set(&self) -> HashSet<Item> {
+    pub fn to_set(&self) -> HashSet<Item> {
         let mut set: HashSet<Item> = HashSet::new();
         for item in self.0.iter() {
-            set.insert(item.clone());
+            set.insert(*item);
         }
 
         set
@@ -32,35 +37,42 @@ impl Compartment {
     pub fn shared_items(&self, other: &Compartment) -> Vec<Item> {
         let mut shared = vec![];
         for item in self.to_set().intersection(&other.to_set()) {
-            shared.push(item.clone())
+            shared.push(*item)
         }
 
         shared
     }
 }
 
-struct Rucksack {
+pub struct Rucksack {
     pub both_compartments: Compartment,
     pub first_compartment: Compartment,
     pub second_compartment: Compartment,
 }
 
 impl std::str::FromStr for Rucksack {
-    type Err = ();
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some((idx, _)) = s.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(Error::parse(DAY, s, idx + 1, "items must be ASCII letters"));
+        }
         if s.len() % 2 == 1 {
-            println!("Bad line length (not even)");
-            return Err(());
+            return Err(Error::parse(
+                DAY,
+                s,
+                s.len(),
+                "rucksack has an odd number of items",
+            ));
         }
 
         let split_idx = s.len() / 2;
         let (first, second) = s.split_at(split_idx);
 
         Ok(Rucksack {
-            both_compartments: Compartment(s.chars().map(|c| Item(c)).collect()),
-            first_compartment: Compartment(first.chars().map(|c| Item(c)).collect()),
-            second_compartment: Compartment(second.chars().map(|c| Item(c)).collect()),
+            both_compartments: Compartment(s.chars().map(Item).collect()),
+            first_compartment: Compartment(first.chars().map(Item).collect()),
+            second_compartment: Compartment(second.chars().map(Item).collect()),
         })
     }
 }
@@ -69,7 +81,7 @@ impl Rucksack {
     pub fn both_to_set(&self) -> HashSet<Item> {
         let mut set: HashSet<Item> = HashSet::new();
         for item in self.both_compartments.0.iter() {
-            set.insert(item.clone());
+            set.insert(*item);
         }
 
         set
@@ -80,16 +92,17 @@ impl Rucksack {
     }
 }
 
-fn parse_rucksacks(rucksack_lines: &Vec<String>) -> impl Iterator<Item = Rucksack> + '_ {
+pub fn parse_rucksacks(rucksack_lines: &[String]) -> impl Iterator<Item = Result<Rucksack>> + '_ {
     rucksack_lines
         .iter()
-        .map(|line| line.parse().expect("Could not parse rucksack line"))
+        .enumerate()
+        .map(|(idx, line)| line.parse().map_err(|err: Error| err.offset_lines(idx)))
 }
 
-struct RucksackGroup(Vec<Rucksack>);
+pub struct RucksackGroup<'a>(pub &'a [Rucksack]);
 
-impl RucksackGroup {
-    pub fn common_item(&self) -> Item {
+impl RucksackGroup<'_> {
+    pub fn common_item(&self) -> Option<Item> {
         let items: Vec<HashSet<Item>> = self
             .0
             .iter()
@@ -97,32 +110,22 @@ impl RucksackGroup {
             .collect();
         let mut intermediate_intersection: HashSet<Item> = HashSet::new();
         for item in items[1].intersection(&items[0]) {
-            intermediate_intersection.insert(item.clone());
+            intermediate_intersection.insert(*item);
         }
         intermediate_intersection
             .intersection(&items[2])
             .next()
-            .expect("Cannot find item")
-            .clone()
+            .copied()
     }
 }
 
-fn parse_rucksack_groups(rucksack_lines: &Vec<String>) -> Vec<RucksackGroup> {
-    let mut groups = vec![];
-    let mut group = vec![];
-    for rucksack in parse_rucksacks(rucksack_lines) {
-        group.push(rucksack);
-        if group.len() == 3 {
-            groups.push(RucksackGroup(group));
-            group = vec![];
-        }
-    }
-
-    groups
+pub fn parse_rucksack_groups(rucksacks: &[Rucksack]) -> Vec<RucksackGroup<'_>> {
+    rucksacks.chunks_exact(3).map(RucksackGroup).collect()
 }
 
-pub fn duplicate_priority_sum(rucksack_lines: &Vec<String>) -> u32 {
-    parse_rucksacks(rucksack_lines)
+pub fn duplicate_priority_sum(rucksacks: &[Rucksack]) -> u32 {
+    rucksacks
+        .iter()
         .map(|rucksack| {
             rucksack
                 .duplicates()
@@ -133,14 +136,48 @@ pub fn duplicate_priority_sum(rucksack_lines: &Vec<String>) -> u32 {
         .sum()
 }
 
-pub fn common_item_priority_sum(rucksack_lines: &Vec<String>) -> u32 {
-    parse_rucksack_groups(rucksack_lines)
+pub fn common_item_priority_sum(rucksacks: &[Rucksack]) -> Result<u32> {
+    parse_rucksack_groups(rucksacks)
         .iter()
-        .map(|group| group.common_item().priority())
+        .enumerate()
+        .map(|(idx, group)| {
+            group
+                .common_item()
+                .map(|item| item.priority())
+                .ok_or(Error::solve(
+                    DAY,
+                    format!("group {} has no item in common", idx + 1),
+                ))
+        })
         .sum()
 }
 
+pub struct RucksackReorganization;
+
+impl Solution for RucksackReorganization {
+    const DAY: u8 = DAY;
+
+    type Model = Vec<Rucksack>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        parse_rucksacks(&crate::util::lines(input)).collect()
+    }
+
+    fn part1(model: &Self::Model) -> Result<Self::Answer> {
+        Ok(duplicate_priority_sum(model))
+    }
+
+    fn part2(model: &Self::Model) -> Result<Self::Answer> {
+        common_item_priority_sum(model)
+    }
+}
+
+#[cfg(test)]
 mod tests {
+    use crate::error::Error;
+    use crate::solution::Solution;
+
     const RUCKSACKS: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
 PmmdzqPrVvPwwTWBwg
@@ -151,7 +188,9 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
     #[test]
     fn test_parse_rucksacks() {
         let rucksack_lines = crate::util::testcase_to_input(RUCKSACKS);
-        let rucksacks: Vec<super::Rucksack> = super::parse_rucksacks(&rucksack_lines).collect();
+        let rucksacks: Vec<super::Rucksack> = super::parse_rucksacks(&rucksack_lines)
+            .collect::<crate::error::Result<_>>()
+            .unwrap();
         assert_eq!(rucksacks.len(), 6);
         for rucksack in rucksacks {
             assert_eq!(
@@ -163,15 +202,17 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_parse_rucksack_groups() {
-        let rucksack_lines = crate::util::testcase_to_input(RUCKSACKS);
-        let rucksack_groups = super::parse_rucksack_groups(&rucksack_lines);
+        let rucksacks = super::RucksackReorganization::parse(RUCKSACKS).unwrap();
+        let rucksack_groups = super::parse_rucksack_groups(&rucksacks);
         assert_eq!(rucksack_groups.len(), 2)
     }
 
     #[test]
     fn test_rucksack_duplicates() {
         let rucksack_lines = crate::util::testcase_to_input(RUCKSACKS);
-        let rucksacks: Vec<super::Rucksack> = super::parse_rucksacks(&rucksack_lines).collect();
+        let rucksacks: Vec<super::Rucksack> = super::parse_rucksacks(&rucksack_lines)
+            .collect::<crate::error::Result<_>>()
+            .unwrap();
 
         assert_eq!(rucksacks[0].duplicates(), vec![super::Item('p')]);
         assert_eq!(rucksacks[1].duplicates(), vec![super::Item('L')]);
@@ -183,23 +224,39 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_duplicate_priority_sum() {
-        let rucksack_lines = crate::util::testcase_to_input(RUCKSACKS);
-        let sum = super::duplicate_priority_sum(&rucksack_lines);
-        assert_eq!(sum, 157)
+        let rucksacks = super::RucksackReorganization::parse(RUCKSACKS).unwrap();
+        let sum = super::RucksackReorganization::part1(&rucksacks);
+        assert_eq!(sum.unwrap(), 157)
+    }
+
+    #[test]
+    fn test_parse_rucksacks_invalid() {
+        let err = super::RucksackReorganization::parse("vJrwpWtwJgWr\nabc").err();
+        assert!(matches!(
+            err,
+            Some(Error::Parse {
+                day: 3,
+                line: 2,
+                column: 3,
+                ..
+            })
+        ));
+        let err = super::RucksackReorganization::parse("vJrw-WtwJgWr").err();
+        assert!(matches!(err, Some(Error::Parse { column: 5, .. })));
     }
 
     #[test]
     fn test_rucksack_group_common_item() {
-        let rucksack_lines = crate::util::testcase_to_input(RUCKSACKS);
-        let rucksack_groups = super::parse_rucksack_groups(&rucksack_lines);
-        assert_eq!(rucksack_groups[0].common_item().0, 'r');
-        assert_eq!(rucksack_groups[1].common_item().0, 'Z');
+        let rucksacks = super::RucksackReorganization::parse(RUCKSACKS).unwrap();
+        let rucksack_groups = super::parse_rucksack_groups(&rucksacks);
+        assert_eq!(rucksack_groups[0].common_item(), Some(super::Item('r')));
+        assert_eq!(rucksack_groups[1].common_item(), Some(super::Item('Z')));
     }
 
     #[test]
     fn test_common_item_priority_sum() {
-        let rucksack_lines = crate::util::testcase_to_input(RUCKSACKS);
-        let sum = super::common_item_priority_sum(&rucksack_lines);
-        assert_eq!(sum, 70)
+        let rucksacks = super::RucksackReorganization::parse(RUCKSACKS).unwrap();
+        let sum = super::RucksackReorganization::part2(&rucksacks);
+        assert_eq!(sum.unwrap(), 70)
     }
 }
@@ -1,15 +1,32 @@
-use std::{num::ParseIntError, ops::RangeInclusive, str::FromStr};
+use std::{ops::RangeInclusive, str::FromStr};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+const DAY: u8 = 4;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-struct Assignment(RangeInclusive<u32>);
+pub struct Assignment(pub RangeInclusive<u32>);
 
 impl FromStr for Assignment {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let range: Vec<&str> = s.splitn(2, "-").collect();
-        let begin: u32 = range[0].parse()?;
-        let end: u32 = range[1].parse()?;
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (begin_str, end_str) = s.split_once("-").ok_or(Error::parse(
+            DAY,
+            s,
+            s.chars().count() + 1,
+            "expected a range like 2-4",
+        ))?;
+        let begin: u32 = begin_str
+            .parse()
+            .map_err(|_| Error::parse(DAY, s, 1, "invalid section ID"))?;
+        let end: u32 = end_str.parse().map_err(|_| {
+            Error::parse(DAY, s, begin_str.chars().count() + 2, "invalid section ID")
+        })?;
+        if end < begin {
+            return Err(Error::parse(DAY, s, 1, "range ends before it starts"));
+        }
         Ok(Assignment(RangeInclusive::new(begin, end)))
     }
 }
@@ -34,24 +51,31 @@ impl Assignment {
     }
 }
 
-struct AssignmentPair {
-    first: Assignment,
-    second: Assignment,
+pub struct AssignmentPair {
+    pub first: Assignment,
+    pub second: Assignment,
 }
 
 impl FromStr for AssignmentPair {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let assignments: Vec<&str> = s.splitn(2, ",").collect();
-        let first: Assignment = assignments[0].parse()?;
-        let second: Assignment = assignments[1].parse()?;
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (first_str, second_str) = s.split_once(",").ok_or(Error::parse(
+            DAY,
+            s,
+            s.chars().count() + 1,
+            "expected two comma-separated assignments",
+        ))?;
+        let first: Assignment = first_str.parse().map_err(|err: Error| err.within(s, 0))?;
+        let second: Assignment = second_str
+            .parse()
+            .map_err(|err: Error| err.within(s, first_str.len() + 1))?;
         Ok(AssignmentPair { first, second })
     }
 }
 
 impl AssignmentPair {
-    fn smallest_assignment(&self) -> &Assignment {
+    pub fn smallest_assignment(&self) -> &Assignment {
         std::cmp::min(&self.first, &self.second)
     }
 
@@ -88,26 +112,52 @@ impl AssignmentPair {
     }
 }
 
-fn parse_assignment_pairs(lines: &Vec<String>) -> impl Iterator<Item = AssignmentPair> + '_ {
-    lines.iter().map(|l| {
+pub fn parse_assignment_pairs(
+    lines: &[String],
+) -> impl Iterator<Item = Result<AssignmentPair>> + '_ {
+    lines.iter().enumerate().map(|(idx, l)| {
         l.parse::<AssignmentPair>()
-            .expect("Could not parse assignment pair line")
+            .map_err(|err| err.offset_lines(idx))
     })
 }
 
-pub fn full_subset_assignment_pairs_count(lines: &Vec<String>) -> usize {
-    parse_assignment_pairs(lines)
+pub fn full_subset_assignment_pairs_count(pairs: &[AssignmentPair]) -> usize {
+    pairs
+        .iter()
         .filter(|pair| pair.smallest_is_subset())
         .count()
 }
 
-pub fn any_overlap_assignment_pairs_count(lines: &Vec<String>) -> usize {
-    parse_assignment_pairs(lines)
-        .filter(|pair| pair.overlaps())
-        .count()
+pub fn any_overlap_assignment_pairs_count(pairs: &[AssignmentPair]) -> usize {
+    pairs.iter().filter(|pair| pair.overlaps()).count()
+}
+
+pub struct CampCleanup;
+
+impl Solution for CampCleanup {
+    const DAY: u8 = DAY;
+
+    type Model = Vec<AssignmentPair>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        parse_assignment_pairs(&crate::util::lines(input)).collect()
+    }
+
+    fn part1(model: &Self::Model) -> Result<Self::Answer> {
+        Ok(full_subset_assignment_pairs_count(model))
+    }
+
+    fn part2(model: &Self::Model) -> Result<Self::Answer> {
+        Ok(any_overlap_assignment_pairs_count(model))
+    }
 }
 
+#[cfg(test)]
 mod tests {
+    use crate::error::Error;
+    use crate::solution::Solution;
+
     const ASSIGNMENTS: &str = "2-4,6-8
 2-3,4-5
 5-7,7-9
@@ -118,8 +168,9 @@ mod tests {
     #[test]
     fn test_parse_assignment_pairs() {
         let assignments = crate::util::testcase_to_input(ASSIGNMENTS);
-        let pairs: Vec<super::AssignmentPair> =
-            super::parse_assignment_pairs(&assignments).collect();
+        let pairs: Vec<super::AssignmentPair> = super::parse_assignment_pairs(&assignments)
+            .collect::<crate::error::Result<_>>()
+            .unwrap();
         assert_eq!(pairs.len(), 6);
         assert_eq!(pairs[0].first, super::Assignment(2..=4));
         assert_eq!(pairs[0].second, super::Assignment(6..=8));
@@ -127,26 +178,45 @@ mod tests {
         assert_eq!(pairs[5].second, super::Assignment(4..=8));
     }
 
+    #[test]
+    fn test_parse_assignment_pairs_invalid() {
+        let err = super::CampCleanup::parse("2-4,6-8\n2-4;6-8").err();
+        assert!(matches!(
+            err,
+            Some(Error::Parse {
+                day: 4,
+                line: 2,
+                column: 8,
+                ..
+            })
+        ));
+        let err = super::CampCleanup::parse("2-4,6-x").err();
+        assert!(matches!(err, Some(Error::Parse { column: 7, .. })));
+        let err = super::CampCleanup::parse("4-2,6-8").err();
+        assert!(matches!(err, Some(Error::Parse { column: 1, .. })));
+    }
+
     #[test]
     fn test_smallest_assignment() {
         let assignments = crate::util::testcase_to_input(ASSIGNMENTS);
-        let pairs: Vec<super::AssignmentPair> =
-            super::parse_assignment_pairs(&assignments).collect();
+        let pairs: Vec<super::AssignmentPair> = super::parse_assignment_pairs(&assignments)
+            .collect::<crate::error::Result<_>>()
+            .unwrap();
         assert_eq!(*pairs[3].smallest_assignment(), super::Assignment(3..=7));
         assert_eq!(*pairs[4].smallest_assignment(), super::Assignment(6..=6));
     }
 
     #[test]
     fn test_full_subset_assignment_pairs_count() {
-        let assignments = crate::util::testcase_to_input(ASSIGNMENTS);
-        let count = super::full_subset_assignment_pairs_count(&assignments);
-        assert_eq!(count, 2);
+        let pairs = super::CampCleanup::parse(ASSIGNMENTS).unwrap();
+        let count = super::CampCleanup::part1(&pairs);
+        assert_eq!(count.unwrap(), 2);
     }
 
     #[test]
     fn test_any_overlap_assignment_pairs_count() {
-        let assignments = crate::util::testcase_to_input(ASSIGNMENTS);
-        let count = super::any_overlap_assignment_pairs_count(&assignments);
-        assert_eq!(count, 4);
+        let pairs = super::CampCleanup::parse(ASSIGNMENTS).unwrap();
+        let count = super::CampCleanup::part2(&pairs);
+        assert_eq!(count.unwrap(), 4);
     }
 }
@@ -1,72 +1,114 @@
-use std::{collections::HashMap, num::ParseIntError, str::FromStr};
+use std::{collections::HashMap, str::FromStr};
 
 use lazy_static::lazy_static;
 use regex::Regex;
 
-#[derive(Clone, Copy)]
-struct Crate(char);
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::Normalization;
 
-struct CrateRow(Vec<Option<Crate>>);
+const DAY: u8 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crate(pub char);
+
+pub struct CrateRow(pub Vec<Option<Crate>>);
 
 impl FromStr for CrateRow {
-    type Err = ();
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        println!("CRATE ROW: {}", s);
+    fn from_str(s: &str) -> Result<Self> {
         let mut crates = vec![];
         for (idx, chunk) in s.chars().collect::<Vec<char>>().chunks(4).enumerate() {
-            if chunk[0] == '[' {
-                println!("* crate: {}", chunk[1]);
-                crates.push(Some(Crate(chunk[1])))
-            } else {
-                println!("* no crate");
-                crates.push(None)
+            let column = idx * 4 + 1;
+            match chunk {
+                ['[', c, ']', ..] if c.is_ascii_alphabetic() => crates.push(Some(Crate(*c))),
+                ['[', ..] => return Err(Error::parse(DAY, s, column, "expected a crate like [A]")),
+                _ => match chunk.iter().position(|c| *c != ' ') {
+                    None => crates.push(None),
+                    Some(offset) => {
+                        return Err(Error::parse(
+                            DAY,
+                            s,
+                            column + offset,
+                            "expected a crate like [A] or blank space",
+                        ))
+                    }
+                },
             }
         }
         Ok(CrateRow(crates))
     }
 }
 
-struct Stack(Vec<Crate>);
+#[derive(Clone)]
+pub struct Stack(pub Vec<Crate>);
 
-struct Stacks(HashMap<u32, Stack>);
+#[derive(Clone)]
+pub struct Stacks(HashMap<u32, Stack>);
+
+/// The crane model used to move crates between stacks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crane {
+    /// Moves crates one at a time, so a moved group ends up in reverse order.
+    CrateMover9000,
+    /// Moves several crates at once, so a moved group keeps its order.
+    CrateMover9001,
+}
 
 impl Stacks {
-    fn move_crates(&mut self, count: usize, from_id: u32, to_id: u32) {
-        let from: &mut Stack = self.0.get_mut(&from_id).expect("Could not find stack");
+    pub fn stack(&self, id: u32) -> Option<&Stack> {
+        self.0.get(&id)
+    }
+
+    pub fn move_crates(
+        &mut self,
+        count: usize,
+        from_id: u32,
+        to_id: u32,
+        crane: Crane,
+    ) -> Result<()> {
+        let from: &mut Stack = self
+            .0
+            .get_mut(&from_id)
+            .ok_or(Error::solve(DAY, format!("could not find stack {from_id}")))?;
         let mut to_move = Vec::with_capacity(count);
         for _n in 0..count {
             if let Some(c) = from.0.pop() {
                 to_move.push(c);
             } else {
-                panic!("** WARNING ** NO MORE CRATES IN {from_id}, FAILING");
+                return Err(Error::solve(
+                    DAY,
+                    format!("no more crates in stack {from_id}"),
+                ));
             }
         }
 
-        let to: &mut Stack = self.0.get_mut(&to_id).expect("Could not find stack");
-        for c in to_move.iter().rev() {
-            to.0.push(*c);
+        let to: &mut Stack = self
+            .0
+            .get_mut(&to_id)
+            .ok_or(Error::solve(DAY, format!("could not find stack {to_id}")))?;
+        match crane {
+            Crane::CrateMover9000 => to.0.extend(to_move),
+            Crane::CrateMover9001 => to.0.extend(to_move.iter().rev()),
         }
-        println!("* to stack: {}", to.0.len())
+        Ok(())
     }
 
-    pub fn follow_instructions(&mut self, instructions: Vec<Instruction>) {
+    pub fn follow_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        crane: Crane,
+    ) -> Result<()> {
         for instruction in instructions {
-            println!("Instruction: {:?}", instruction);
-            self.move_crates(instruction.count, instruction.from_id, instruction.to_id);
-            self.ordered();
-        }
-    }
-
-    fn ordered(&self) {
-        let mut keys: Vec<&u32> = self.0.keys().collect();
-        keys.sort();
-        for key in keys {
-            let stack = self.0.get(key).expect("Inexplicably cannot find Stack");
-            let stack_len = stack.0.len();
-            print!("{key}: {stack_len}, ");
+            self.move_crates(
+                instruction.count,
+                instruction.from_id,
+                instruction.to_id,
+                crane,
+            )?;
         }
-        println!("");
+        Ok(())
     }
 
     pub fn top_crates(&self) -> String {
@@ -88,9 +130,9 @@ impl Stacks {
 }
 
 impl FromStr for Stacks {
-    type Err = ();
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         let lines = s.split("\n").collect::<Vec<&str>>();
         let stack_height: usize = lines.len() - 1;
         let mut stacks: HashMap<u32, Stack> = HashMap::with_capacity(stack_height);
@@ -100,28 +142,37 @@ impl FromStr for Stacks {
         for (idx, line) in lines.iter().enumerate() {
             if idx == stack_height {
                 lazy_static! {
-                    static ref IDS_SPLIT_RE: Regex =
-                        Regex::new(" +").expect("Could not compile split regex");
+                    static ref IDS_RE: Regex =
+                        Regex::new("\\S+").expect("Could not compile ID regex");
                 }
-                ids = IDS_SPLIT_RE
-                    .split(line.trim())
-                    .map(|id| id.parse::<u32>().expect("Could not parse number"))
-                    .collect();
-                for id in &ids {
-                    stacks.insert(*id, Stack(vec![]));
+                for id in IDS_RE.find_iter(line) {
+                    let id_value: u32 = id.as_str().parse().map_err(|_| {
+                        Error::parse(DAY, line, id.start() + 1, "invalid stack ID")
+                            .offset_lines(idx)
+                    })?;
+                    ids.push(id_value);
+                    stacks.insert(id_value, Stack(vec![]));
                 }
             } else {
-                let crate_line = line.parse().expect("could not parse crate line");
+                let crate_line = line.parse().map_err(|err: Error| err.offset_lines(idx))?;
                 crates.push(crate_line);
             }
         }
 
-        for row in crates.iter().rev() {
+        for (row_idx, row) in crates.iter().enumerate().rev() {
             for (idx, maybe_crate) in row.0.iter().enumerate() {
-                let stack: &mut Stack = stacks
-                    .get_mut(&ids[idx])
-                    .expect("Could not find stack in stacks");
                 if let Some(c) = maybe_crate {
+                    let id = ids.get(idx).ok_or(
+                        Error::parse(
+                            DAY,
+                            lines[row_idx],
+                            idx * 4 + 1,
+                            "crate is not above a stack",
+                        )
+                        .offset_lines(row_idx),
+                    )?;
+                    let stack: &mut Stack =
+                        stacks.get_mut(id).expect("Could not find stack in stacks");
                     stack.0.push(*c);
                 }
             }
@@ -132,67 +183,131 @@ impl FromStr for Stacks {
 }
 
 #[derive(Debug, PartialEq)]
-struct Instruction {
-    count: usize,
-    from_id: u32,
-    to_id: u32,
+pub struct Instruction {
+    pub count: usize,
+    pub from_id: u32,
+    pub to_id: u32,
 }
 
 impl FromStr for Instruction {
-    type Err = ParseIntError;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         lazy_static! {
             static ref INSTRUCTION_RE: Regex =
                 Regex::new("^move (?P<count>\\d+) from (?P<from>\\d+) to (?P<to>\\d+)$")
                     .expect("Could not compile instruction regex");
         }
 
-        let caps = INSTRUCTION_RE.captures(s).expect("Could not match");
-        let count = caps
-            .name("count")
-            .expect("Could not find count")
-            .as_str()
-            .parse()?;
-        let from_id = caps
-            .name("from")
-            .expect("Could not find count")
-            .as_str()
-            .parse()?;
-        let to_id = caps
-            .name("to")
-            .expect("Could not find to")
-            .as_str()
-            .parse()?;
+        let caps = INSTRUCTION_RE.captures(s).ok_or(Error::parse(
+            DAY,
+            s,
+            1,
+            "expected `move N from N to N`",
+        ))?;
+        let number = |name: &str| -> Result<u32> {
+            let m = caps.name(name).expect("Could not find capture group");
+            m.as_str()
+                .parse()
+                .map_err(|_| Error::parse(DAY, s, m.start() + 1, "number is too large"))
+        };
 
         Ok(Instruction {
-            count,
-            from_id,
-            to_id,
+            count: number("count")? as usize,
+            from_id: number("from")?,
+            to_id: number("to")?,
         })
     }
 }
 
-fn parse_instructions(manual: &str) -> Vec<Instruction> {
+pub fn parse_instructions(manual: &str) -> Result<Vec<Instruction>> {
     manual
         .split("\n")
-        .map(|line| {
+        .enumerate()
+        .map(|(idx, line)| {
             line.parse::<Instruction>()
-                .expect("Could not parse instruction")
+                .map_err(|err| err.offset_lines(idx))
         })
         .collect()
 }
 
-pub fn top_stacked_crates(stacks_and_instructions: String) -> String {
-    let parts: Vec<&str> = stacks_and_instructions.splitn(2, "\n\n").collect();
-    let mut stacks: Stacks = parts[0].parse().expect("Could not parse stacks");
-    let instructions = parse_instructions(parts[1]);
-    stacks.ordered();
-    stacks.follow_instructions(instructions);
-    stacks.top_crates()
+pub struct Procedure {
+    pub stacks: Stacks,
+    pub instructions: Vec<Instruction>,
+}
+
+/// Parses the stacks drawing and the instructions that follow it. Any further blank-line
+/// separated blocks are read as more instructions.
+pub fn parse_procedure(stacks_and_instructions: &str) -> Result<Procedure> {
+    let mut blocks = crate::util::blocks(stacks_and_instructions);
+    let Some(drawing) = blocks.next() else {
+        return Err(Error::parse(DAY, "", 1, "expected a drawing of the stacks"));
+    };
+    let stacks: Stacks = drawing
+        .text()
+        .parse()
+        .map_err(|err: Error| err.offset_lines(drawing.line - 1))?;
+
+    let mut instructions = vec![];
+    for manual in blocks {
+        instructions.extend(
+            parse_instructions(&manual.text()).map_err(|err| err.offset_lines(manual.line - 1))?,
+        );
+    }
+    if instructions.is_empty() {
+        return Err(Error::parse(
+            DAY,
+            "",
+            1,
+            "expected a blank line between the stacks and the instructions",
+        )
+        .offset_lines(drawing.line - 1 + drawing.lines.len()));
+    }
+
+    Ok(Procedure {
+        stacks,
+        instructions,
+    })
+}
+
+pub fn top_stacked_crates(procedure: &Procedure, crane: Crane) -> Result<String> {
+    let mut stacks = procedure.stacks.clone();
+    stacks.follow_instructions(&procedure.instructions, crane)?;
+    Ok(stacks.top_crates())
+}
+
+pub struct SupplyStacks;
+
+impl Solution for SupplyStacks {
+    const DAY: u8 = DAY;
+
+    // The drawing pads its rows with spaces, so line endings are left alone.
+    const NORMALIZATION: Normalization = Normalization {
+        strip_trailing_whitespace: false,
+        ..Normalization::DEFAULT
+    };
+
+    type Model = Procedure;
+    type Answer = String;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        parse_procedure(input)
+    }
+
+    fn part1(model: &Self::Model) -> Result<Self::Answer> {
+        top_stacked_crates(model, Crane::CrateMover9000)
+    }
+
+    fn part2(model: &Self::Model) -> Result<Self::Answer> {
+        top_stacked_crates(model, Crane::CrateMover9001)
+    }
 }
 
+#[cfg(test)]
 mod tests {
+    use crate::error::Error;
+    use crate::solution::Solution;
+
     const CRATES: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n
 move 1 from 2 to 1
 move 3 from 1 to 3
@@ -202,7 +317,7 @@ move 1 from 1 to 2";
     #[test]
     fn test_parse_instructions() {
         let parts: Vec<&str> = CRATES.splitn(2, "\n\n").collect();
-        let instructions: Vec<super::Instruction> = super::parse_instructions(parts[1]);
+        let instructions: Vec<super::Instruction> = super::parse_instructions(parts[1]).unwrap();
         assert_eq!(instructions.len(), 4);
         assert_eq!(
             instructions[0],
@@ -214,9 +329,86 @@ move 1 from 1 to 2";
         )
     }
 
+    #[test]
+    fn test_top_stacked_crates_crate_mover_9000() {
+        let procedure = super::SupplyStacks::parse(CRATES).unwrap();
+        let top = super::SupplyStacks::part1(&procedure);
+        assert_eq!(top.unwrap(), "CMZ".to_string())
+    }
+
+    #[test]
+    fn test_move_crates() {
+        use super::{Crane, Crate};
+
+        let procedure = super::SupplyStacks::parse(CRATES).unwrap();
+        let mut stacks = procedure.stacks.clone();
+        stacks.move_crates(2, 1, 3, Crane::CrateMover9000).unwrap();
+        assert_eq!(
+            stacks.stack(3).unwrap().0,
+            vec![Crate('P'), Crate('N'), Crate('Z')]
+        );
+        let mut stacks = procedure.stacks.clone();
+        stacks.move_crates(2, 1, 3, Crane::CrateMover9001).unwrap();
+        assert_eq!(
+            stacks.stack(3).unwrap().0,
+            vec![Crate('P'), Crate('Z'), Crate('N')]
+        );
+    }
+
     #[test]
     fn test_top_stacked_crates() {
-        let top = super::top_stacked_crates(CRATES.to_string());
-        assert_eq!(top, "MCD".to_string())
+        let procedure = super::SupplyStacks::parse(CRATES).unwrap();
+        let top = super::SupplyStacks::part2(&procedure);
+        assert_eq!(top.unwrap(), "MCD".to_string())
+    }
+
+    #[test]
+    fn test_parse_procedure_invalid() {
+        let bad_instruction = CRATES.replace("move 3 from 1 to 3", "move 3 form 1 to 3");
+        let err = super::SupplyStacks::parse(&bad_instruction).err();
+        assert!(matches!(
+            err,
+            Some(Error::Parse {
+                day: 5,
+                line: 7,
+                column: 1,
+                ..
+            })
+        ));
+        let bad_crate = CRATES.replace("[C]", "[C ");
+        let err = super::SupplyStacks::parse(&bad_crate).err();
+        assert!(matches!(
+            err,
+            Some(Error::Parse {
+                line: 2,
+                column: 5,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_procedure_tolerant_blocks() {
+        let crlf = format!(
+            "\r\n{}\r\n\r\n",
+            CRATES.replace("\n\n", "\n   \n\n").replace('\n', "\r\n")
+        );
+        let procedure = super::SupplyStacks::parse(&crlf).unwrap();
+        assert_eq!(procedure.instructions.len(), 4);
+        assert_eq!(super::SupplyStacks::part1(&procedure).unwrap(), "CMZ");
+
+        let bad_instruction = crlf.replace("move 2 from 2 to 1", "move 2 from 2 to");
+        let err = super::SupplyStacks::parse(&bad_instruction).err();
+        assert!(matches!(err, Some(Error::Parse { line: 10, .. })));
+        let err = super::SupplyStacks::parse(" 1 \n\n").err();
+        assert!(matches!(err, Some(Error::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_top_stacked_crates_empty_stack() {
+        let procedure =
+            super::SupplyStacks::parse(&CRATES.replace("move 1 from 2 to 1", "move 4 from 2 to 1"))
+                .unwrap();
+        assert!(super::SupplyStacks::part2(&procedure).is_err())
     }
 }
@@ -1,40 +1,754 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::io::Read;
+use std::ops::Range;
 
-const MARKER_LENGTH: usize = 14;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 
-pub fn find_start_of_packet_marker(datastream: &str) -> Option<usize> {
-    let count = datastream.len();
-    let chars: Vec<char> = datastream.chars().collect();
-    for (idx, c) in chars.iter().enumerate() {
-        if idx + MARKER_LENGTH - 1 >= count {
-            break;
+const DAY: u8 = 6;
+
+pub const START_OF_PACKET_LENGTH: usize = 4;
+pub const START_OF_MESSAGE_LENGTH: usize = 14;
+
+/// Whether a marker may reuse characters from the previous marker.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MarkerOverlap {
+    /// Report every position where the last `marker_length` characters are distinct.
+    Overlapping,
+    /// Start looking for the next marker only after the previous one ends.
+    NonOverlapping,
+}
+
+/// How many times each item occurs in a [`Window`].
+trait Counts<T> {
+    /// Adds an occurrence of `item`, returning its new count.
+    fn increment(&mut self, item: T) -> usize;
+    /// Removes an occurrence of `item`, returning its new count.
+    fn decrement(&mut self, item: T) -> usize;
+    fn clear(&mut self);
+}
+
+impl<T: Eq + Hash> Counts<T> for HashMap<T, usize> {
+    fn increment(&mut self, item: T) -> usize {
+        let count = self.entry(item).or_insert(0);
+        *count += 1;
+        *count
+    }
+
+    fn decrement(&mut self, item: T) -> usize {
+        let count = self.get_mut(&item).expect("item is in the window");
+        *count -= 1;
+        *count
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self);
+    }
+}
+
+/// Counts bytes in a flat array, which avoids hashing in the byte-mode detector.
+struct ByteCounts([usize; 256]);
+
+impl Default for ByteCounts {
+    fn default() -> Self {
+        ByteCounts([0; 256])
+    }
+}
+
+impl Counts<u8> for ByteCounts {
+    fn increment(&mut self, item: u8) -> usize {
+        self.0[item as usize] += 1;
+        self.0[item as usize]
+    }
+
+    fn decrement(&mut self, item: u8) -> usize {
+        self.0[item as usize] -= 1;
+        self.0[item as usize]
+    }
+
+    fn clear(&mut self) {
+        self.0 = [0; 256];
+    }
+}
+
+/// A sliding window that tracks how many items occur more than once, so checking whether the
+/// window is a marker is O(1) per step.
+struct Window<T, C = HashMap<T, usize>> {
+    length: usize,
+    items: VecDeque<T>,
+    counts: C,
+    repeated: usize,
+}
+
+impl<T: Copy, C: Counts<T> + Default> Window<T, C> {
+    fn new(length: usize) -> Window<T, C> {
+        Window {
+            length,
+            items: VecDeque::with_capacity(length + 1),
+            counts: C::default(),
+            repeated: 0,
+        }
+    }
+
+    /// Adds an item, returning whether the window now holds `length` distinct items.
+    fn push(&mut self, item: T) -> bool {
+        self.items.push_back(item);
+        if self.counts.increment(item) == 2 {
+            self.repeated += 1;
+        }
+
+        if self.items.len() > self.length {
+            let old = self.items.pop_front().expect("window is not empty");
+            if self.counts.decrement(old) == 1 {
+                self.repeated -= 1;
+            }
+        }
+
+        self.items.len() == self.length && self.repeated == 0
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.counts.clear();
+        self.repeated = 0;
+    }
+}
+
+/// Iterates over every marker in a datastream, yielding the number of characters processed when
+/// each marker ends. Offsets count `char`s, not bytes; see [`MarkerDetector`] for byte offsets.
+pub struct Markers<'a> {
+    chars: std::str::Chars<'a>,
+    overlap: MarkerOverlap,
+    window: Window<char>,
+    processed: usize,
+}
+
+impl Iterator for Markers<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.window.length == 0 {
+            return None;
+        }
+
+        for c in self.chars.by_ref() {
+            self.processed += 1;
+            if self.window.push(c) {
+                if self.overlap == MarkerOverlap::NonOverlapping {
+                    self.window.clear();
+                }
+                return Some(self.processed);
+            }
+        }
+
+        None
+    }
+}
+
+/// A zero-length marker is never found.
+pub fn markers(datastream: &str, marker_length: usize, overlap: MarkerOverlap) -> Markers<'_> {
+    Markers {
+        chars: datastream.chars(),
+        overlap,
+        window: Window::new(marker_length),
+        processed: 0,
+    }
+}
+
+/// Returns the number of characters processed when the first `marker_length` distinct characters
+/// in a row have been seen. A zero-length marker is never found.
+///
+/// This is the char-mode search: the offset counts `char`s, so for non-ASCII datastreams it is not
+/// a byte index into `datastream`. Use [`find_marker_in_bytes`] to compare and count bytes.
+pub fn find_marker(datastream: &str, marker_length: usize) -> Option<usize> {
+    markers(datastream, marker_length, MarkerOverlap::Overlapping).next()
+}
+
+/// Distances between consecutive marker positions.
+#[derive(Debug, PartialEq)]
+pub struct MarkerGaps {
+    pub markers: usize,
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+    pub mean: Option<f64>,
+}
+
+impl MarkerGaps {
+    pub fn new(positions: &[usize]) -> MarkerGaps {
+        let gaps: Vec<usize> = positions.windows(2).map(|w| w[1] - w[0]).collect();
+        MarkerGaps {
+            markers: positions.len(),
+            min: gaps.iter().min().copied(),
+            max: gaps.iter().max().copied(),
+            mean: if gaps.is_empty() {
+                None
+            } else {
+                Some(gaps.iter().sum::<usize>() as f64 / gaps.len() as f64)
+            },
+        }
+    }
+}
+
+/// A push-style byte-mode marker detector for datastreams that arrive in chunks. Bytes are compared
+/// individually and offsets count bytes, so they match [`find_marker`] only for ASCII datastreams.
+/// Use [`CharMarkerDetector`] to find markers of distinct characters in UTF-8 input.
+pub struct MarkerDetector {
+    window: Window<u8, ByteCounts>,
+    processed: usize,
+    marker: Option<usize>,
+}
+
+impl MarkerDetector {
+    pub fn new(marker_length: usize) -> MarkerDetector {
+        MarkerDetector {
+            window: Window::new(marker_length),
+            processed: 0,
+            marker: None,
+        }
+    }
+
+    /// Feeds one byte. Returns the marker offset once it has been seen.
+    pub fn push_byte(&mut self, byte: u8) -> Option<usize> {
+        if self.marker.is_some() || self.window.length == 0 {
+            return self.marker;
         }
-        let mut unique: HashSet<char> = HashSet::with_capacity(MARKER_LENGTH);
-        unique.insert(c.clone());
-        for n in 1..MARKER_LENGTH {
-            unique.insert(chars[idx + n].clone());
+
+        self.processed += 1;
+        if self.window.push(byte) {
+            self.marker = Some(self.processed);
         }
-        if unique.len() == MARKER_LENGTH {
-            return Some(idx + MARKER_LENGTH);
+        self.marker
+    }
+
+    /// Feeds a chunk of bytes, stopping early once the marker has been seen.
+    pub fn push(&mut self, chunk: &[u8]) -> Option<usize> {
+        for &byte in chunk {
+            if self.push_byte(byte).is_some() {
+                break;
+            }
         }
+        self.marker
     }
 
-    None
+    pub fn marker(&self) -> Option<usize> {
+        self.marker
+    }
+
+    /// The number of bytes consumed so far. Bytes pushed after the marker are not counted.
+    pub fn processed(&self) -> usize {
+        self.processed
+    }
 }
 
+/// A push-style char-mode marker detector. Bytes are decoded as UTF-8, a character split across
+/// chunks is held back until it is complete, and offsets count `char`s like [`find_marker`].
+pub struct CharMarkerDetector {
+    window: Window<char>,
+    pending: Vec<u8>,
+    processed: usize,
+    marker: Option<usize>,
+}
+
+impl CharMarkerDetector {
+    pub fn new(marker_length: usize) -> CharMarkerDetector {
+        CharMarkerDetector {
+            window: Window::new(marker_length),
+            pending: vec![],
+            processed: 0,
+            marker: None,
+        }
+    }
+
+    /// Feeds one character. Returns the marker offset once it has been seen.
+    pub fn push_char(&mut self, c: char) -> Option<usize> {
+        if self.marker.is_some() || self.window.length == 0 {
+            return self.marker;
+        }
+
+        self.processed += 1;
+        if self.window.push(c) {
+            self.marker = Some(self.processed);
+        }
+        self.marker
+    }
+
+    /// Feeds a string, stopping early once the marker has been seen.
+    pub fn push_str(&mut self, chunk: &str) -> Option<usize> {
+        for c in chunk.chars() {
+            if self.push_char(c).is_some() {
+                break;
+            }
+        }
+        self.marker
+    }
+
+    /// Feeds a chunk of UTF-8 bytes. Invalid UTF-8 is reported as an I/O error, unless the
+    /// marker is complete before it. Bytes pushed after the marker are ignored, like
+    /// [`MarkerDetector::push`].
+    pub fn push_bytes(&mut self, chunk: &[u8]) -> Result<Option<usize>> {
+        if self.marker.is_some() {
+            return Ok(self.marker);
+        }
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(chunk);
+        // The valid prefix is fed before any error is raised, so a marker inside it is kept.
+        let (valid, invalid) = match std::str::from_utf8(&pending) {
+            Ok(text) => (text.len(), None),
+            Err(err) => (err.valid_up_to(), err.error_len().map(|_| err)),
+        };
+        let text = std::str::from_utf8(&pending[..valid]).expect("validated above");
+        let marker = self.push_str(text);
+        pending.drain(..valid);
+        self.pending = pending;
+        match invalid {
+            Some(err) if marker.is_none() => {
+                Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err).into())
+            }
+            _ => Ok(marker),
+        }
+    }
+
+    pub fn marker(&self) -> Option<usize> {
+        self.marker
+    }
+
+    /// The number of characters consumed so far. Characters pushed after the marker are not
+    /// counted.
+    pub fn processed(&self) -> usize {
+        self.processed
+    }
+}
+
+pub fn find_marker_in_bytes<I: IntoIterator<Item = u8>>(
+    bytes: I,
+    marker_length: usize,
+) -> Option<usize> {
+    let mut detector = MarkerDetector::new(marker_length);
+    bytes.into_iter().find_map(|byte| detector.push_byte(byte))
+}
+
+/// Reads `reader` in chunks until the byte-mode marker is seen, without buffering the whole
+/// datastream.
+pub fn find_marker_in_reader<R: Read>(
+    mut reader: R,
+    marker_length: usize,
+) -> Result<Option<usize>> {
+    let mut detector = MarkerDetector::new(marker_length);
+    let mut buffer = [0; 8192];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(None),
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(marker) = detector.push(&buffer[..read]) {
+            return Ok(Some(marker));
+        }
+    }
+}
+
+/// Reads UTF-8 from `reader` in chunks until the char-mode marker is seen. The offset counts
+/// `char`s, like [`find_marker`].
+pub fn find_char_marker_in_reader<R: Read>(
+    mut reader: R,
+    marker_length: usize,
+) -> Result<Option<usize>> {
+    let mut detector = CharMarkerDetector::new(marker_length);
+    let mut buffer = [0; 8192];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) if detector.pending.is_empty() => return Ok(None),
+            Ok(0) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "datastream ends in the middle of a UTF-8 character",
+                )
+                .into())
+            }
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(marker) = detector.push_bytes(&buffer[..read])? {
+            return Ok(Some(marker));
+        }
+    }
+}
+
+pub fn find_start_of_packet_marker(datastream: &str) -> Option<usize> {
+    find_marker(datastream, START_OF_PACKET_LENGTH)
+}
+
+pub fn find_start_of_message_marker(datastream: &str) -> Option<usize> {
+    find_marker(datastream, START_OF_MESSAGE_LENGTH)
+}
+
+/// One frame of a datastream: a start-of-packet marker, then a payload that runs up to the next
+/// start-of-message marker. Ranges are `char` offsets into the whole datastream.
+#[derive(Debug, PartialEq)]
+pub struct Frame<'a> {
+    pub packet_marker: Range<usize>,
+    pub payload: Range<usize>,
+    pub payload_text: &'a str,
+    /// `None` when the datastream ends before another start-of-message marker.
+    pub message_marker: Option<Range<usize>>,
+}
+
+/// The byte index of the `char` at `offset`, or the end of `text` if it is shorter.
+fn byte_index(text: &str, offset: usize) -> usize {
+    text.char_indices()
+        .nth(offset)
+        .map_or(text.len(), |(idx, _)| idx)
+}
+
+/// Splits a datastream into frames. Anything before the first start-of-packet marker, or after
+/// a start-of-message marker that is not followed by another packet marker, is not framed.
+pub fn frames(datastream: &str) -> Vec<Frame<'_>> {
+    let mut frames = vec![];
+    let mut rest = datastream;
+    let mut offset = 0;
+    while let Some(packet_end) = find_start_of_packet_marker(rest) {
+        let body = &rest[byte_index(rest, packet_end)..];
+        let message_end = find_start_of_message_marker(body);
+        let payload_length =
+            message_end.map_or(body.chars().count(), |end| end - START_OF_MESSAGE_LENGTH);
+        let payload_start = offset + packet_end;
+        frames.push(Frame {
+            packet_marker: payload_start - START_OF_PACKET_LENGTH..payload_start,
+            payload: payload_start..payload_start + payload_length,
+            payload_text: &body[..byte_index(body, payload_length)],
+            message_marker: message_end
+                .map(|end| payload_start + end - START_OF_MESSAGE_LENGTH..payload_start + end),
+        });
+
+        match message_end {
+            Some(end) => {
+                rest = &body[byte_index(body, end)..];
+                offset = payload_start + end;
+            }
+            None => break,
+        }
+    }
+
+    frames
+}
+
+/// Draws the frames underneath the datastream: `P` for start-of-packet markers, `.` for payloads
+/// and `M` for start-of-message markers.
+pub fn frame_map(datastream: &str, frames: &[Frame]) -> String {
+    let mut map = vec![' '; datastream.chars().count()];
+    for frame in frames {
+        map[frame.packet_marker.clone()].fill('P');
+        map[frame.payload.clone()].fill('.');
+        if let Some(marker) = &frame.message_marker {
+            map[marker.clone()].fill('M');
+        }
+    }
+    let map: String = map.into_iter().collect();
+    format!("{}\n{}", datastream, map.trim_end())
+}
+
+pub struct TuningTrouble;
+
+impl Solution for TuningTrouble {
+    const DAY: u8 = DAY;
+
+    type Model = String;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        Ok(input.to_string())
+    }
+
+    fn part1(model: &Self::Model) -> Result<Self::Answer> {
+        find_start_of_packet_marker(model)
+            .ok_or(Error::solve(DAY, "no start-of-packet marker found"))
+    }
+
+    fn part2(model: &Self::Model) -> Result<Self::Answer> {
+        find_start_of_message_marker(model)
+            .ok_or(Error::solve(DAY, "no start-of-message marker found"))
+    }
+}
+
+#[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     const SIGNAL1: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
     const SIGNAL2: &str = "bvwbjplbgvbhsrlpgdmjqwftvncz";
     const SIGNAL3: &str = "nppdvjthqldpwncqszvftbrmjlhg";
     const SIGNAL4: &str = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
     const SIGNAL5: &str = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
 
+    /// The original marker search, which rebuilds a `HashSet` for every window. It is kept as a
+    /// reference for differential tests against [`super::find_marker`].
+    fn find_marker_reference(datastream: &str, marker_length: usize) -> Option<usize> {
+        let chars: Vec<char> = datastream.chars().collect();
+        let count = chars.len();
+        for (idx, c) in chars.iter().enumerate() {
+            if idx + marker_length > count {
+                break;
+            }
+            let mut unique: HashSet<char> = HashSet::with_capacity(marker_length);
+            unique.insert(*c);
+            for n in 1..marker_length {
+                unique.insert(chars[idx + n]);
+            }
+            if unique.len() == marker_length {
+                return Some(idx + marker_length);
+            }
+        }
+
+        None
+    }
+
     #[test]
     fn test_find_start_of_packet_marker() {
-        assert_eq!(super::find_start_of_packet_marker(SIGNAL1), Some(19));
-        assert_eq!(super::find_start_of_packet_marker(SIGNAL2), Some(23));
-        assert_eq!(super::find_start_of_packet_marker(SIGNAL3), Some(23));
-        assert_eq!(super::find_start_of_packet_marker(SIGNAL4), Some(29));
-        assert_eq!(super::find_start_of_packet_marker(SIGNAL5), Some(26));
+        assert_eq!(super::find_start_of_packet_marker(SIGNAL1), Some(7));
+        assert_eq!(super::find_start_of_packet_marker(SIGNAL2), Some(5));
+        assert_eq!(super::find_start_of_packet_marker(SIGNAL3), Some(6));
+        assert_eq!(super::find_start_of_packet_marker(SIGNAL4), Some(10));
+        assert_eq!(super::find_start_of_packet_marker(SIGNAL5), Some(11));
+    }
+
+    #[test]
+    fn test_find_start_of_message_marker() {
+        assert_eq!(super::find_start_of_message_marker(SIGNAL1), Some(19));
+        assert_eq!(super::find_start_of_message_marker(SIGNAL2), Some(23));
+        assert_eq!(super::find_start_of_message_marker(SIGNAL3), Some(23));
+        assert_eq!(super::find_start_of_message_marker(SIGNAL4), Some(29));
+        assert_eq!(super::find_start_of_message_marker(SIGNAL5), Some(26));
+    }
+
+    #[test]
+    fn test_find_marker_too_short() {
+        assert_eq!(super::find_marker("abc", 4), None);
+        assert_eq!(super::find_marker("abcd", 4), Some(4));
+        assert_eq!(super::find_marker("abcd", 0), None);
+    }
+
+    #[test]
+    fn test_markers() {
+        use super::{markers, MarkerOverlap};
+
+        let all: Vec<usize> = markers("abcabcaab", 3, MarkerOverlap::Overlapping).collect();
+        assert_eq!(all, vec![3, 4, 5, 6, 7]);
+        let separate: Vec<usize> = markers("abcabcaab", 3, MarkerOverlap::NonOverlapping).collect();
+        assert_eq!(separate, vec![3, 6]);
+        assert_eq!(markers("aaaa", 2, MarkerOverlap::Overlapping).next(), None);
+        assert_eq!(
+            markers(
+                SIGNAL1,
+                super::START_OF_MESSAGE_LENGTH,
+                MarkerOverlap::Overlapping
+            )
+            .next(),
+            Some(19)
+        );
+    }
+
+    #[test]
+    fn test_marker_gaps() {
+        use super::MarkerGaps;
+
+        let gaps = MarkerGaps::new(&[4, 6, 12]);
+        assert_eq!(gaps.markers, 3);
+        assert_eq!(gaps.min, Some(2));
+        assert_eq!(gaps.max, Some(6));
+        assert_eq!(gaps.mean, Some(4.0));
+        let single = MarkerGaps::new(&[4]);
+        assert_eq!(single.min, None);
+        assert_eq!(single.mean, None);
+    }
+
+    #[test]
+    fn test_marker_detector_chunks() {
+        for signal in [SIGNAL1, SIGNAL2, SIGNAL3, SIGNAL4, SIGNAL5] {
+            for chunk_size in [1, 3, 7, 64] {
+                let mut detector = super::MarkerDetector::new(super::START_OF_MESSAGE_LENGTH);
+                let found = signal
+                    .as_bytes()
+                    .chunks(chunk_size)
+                    .find_map(|chunk| detector.push(chunk));
+                assert_eq!(found, super::find_start_of_message_marker(signal));
+                assert_eq!(detector.processed(), found.unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_marker_in_reader_and_bytes() {
+        for signal in [SIGNAL1, SIGNAL2, SIGNAL3, SIGNAL4, SIGNAL5] {
+            for length in [
+                super::START_OF_PACKET_LENGTH,
+                super::START_OF_MESSAGE_LENGTH,
+            ] {
+                let expected = super::find_marker(signal, length);
+                let found = super::find_marker_in_reader(signal.as_bytes(), length).unwrap();
+                assert_eq!(found, expected);
+                assert_eq!(
+                    super::find_marker_in_bytes(signal.bytes(), length),
+                    expected
+                );
+            }
+        }
+        assert_eq!(super::find_marker_in_reader(&b"aaaa"[..], 2).unwrap(), None);
+    }
+
+    #[test]
+    fn test_find_marker_utf8() {
+        // é, ü and ö share their leading byte, so byte mode sees repeats that char mode does not.
+        let datastream = "ééüö";
+        assert_eq!(super::find_marker(datastream, 3), Some(4));
+        assert_eq!(find_marker_reference(datastream, 3), Some(4));
+        assert_eq!(super::find_marker_in_bytes(datastream.bytes(), 3), Some(6));
+        assert_eq!(find_marker_reference("ééé", 2), None);
+        // Byte offsets can land inside a character.
+        assert_eq!(super::find_marker("€€a", 2), Some(3));
+        assert_eq!(super::find_marker_in_bytes("€€a".bytes(), 2), Some(2));
+    }
+
+    #[test]
+    fn test_char_marker_detector_split_characters() {
+        let datastream = "€€😀ßaΩ";
+        let expected = super::find_marker(datastream, 4);
+        assert_eq!(expected, Some(5));
+        for chunk_size in [1, 2, 3, 5] {
+            let mut detector = super::CharMarkerDetector::new(4);
+            let mut found = None;
+            for chunk in datastream.as_bytes().chunks(chunk_size) {
+                found = detector.push_bytes(chunk).unwrap();
+                if found.is_some() {
+                    break;
+                }
+            }
+            assert_eq!(found, expected);
+            assert_eq!(detector.processed(), 5);
+        }
+        assert_eq!(
+            super::find_char_marker_in_reader(datastream.as_bytes(), 4).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_detectors_keep_reporting_marker() {
+        let mut bytes = super::MarkerDetector::new(2);
+        let mut chars = super::CharMarkerDetector::new(2);
+        assert_eq!(bytes.push(b"ab"), Some(2));
+        assert_eq!(chars.push_str("ab"), Some(2));
+        assert_eq!(bytes.push(b""), Some(2));
+        assert_eq!(chars.push_str(""), Some(2));
+        assert_eq!(chars.push_bytes(&[]).unwrap(), Some(2));
+        // The first byte of "€" alone does not complete a character.
+        assert_eq!(chars.push_bytes(&[0xe2]).unwrap(), Some(2));
+        assert_eq!(chars.processed(), 2);
+
+        let mut partial = super::CharMarkerDetector::new(2);
+        assert_eq!(partial.push_bytes(&[0xe2]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_char_marker_detector_invalid_utf8() {
+        let mut detector = super::CharMarkerDetector::new(4);
+        assert!(detector.push_bytes(&[b'a', 0xff]).is_err());
+        // The bytes that were not consumed are kept, so the error repeats.
+        assert_eq!(detector.pending, [0xff]);
+        assert_eq!(detector.processed(), 1);
+        assert!(detector.push_bytes(b"bcd").is_err());
+        assert!(super::find_char_marker_in_reader(&[b'a', 0xe2, 0x82][..], 4).is_err());
+    }
+
+    #[test]
+    fn test_char_marker_before_invalid_utf8() {
+        assert_eq!(
+            super::CharMarkerDetector::new(2)
+                .push_bytes(b"ab\xff")
+                .unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            super::find_char_marker_in_reader(&b"abc\xff"[..], 2).unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            super::find_marker_in_reader(&b"abc\xff"[..], 2).unwrap(),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_frames() {
+        use super::Frame;
+
+        let datastream = "aabcdxxyyxxabcdefghijklmnopzz";
+        let frames = super::frames(datastream);
+        assert_eq!(
+            frames,
+            vec![
+                Frame {
+                    packet_marker: 1..5,
+                    payload: 5..10,
+                    payload_text: "xxyyx",
+                    message_marker: Some(10..24),
+                },
+                Frame {
+                    packet_marker: 24..28,
+                    payload: 28..29,
+                    payload_text: "z",
+                    message_marker: None,
+                },
+            ]
+        );
+        assert_eq!(
+            super::frame_map(datastream, &frames),
+            "aabcdxxyyxxabcdefghijklmnopzz\n PPPP.....MMMMMMMMMMMMMMPPPP."
+        );
+        assert!(super::frames("aaaa").is_empty());
+    }
+
+    #[test]
+    fn test_frames_utf8() {
+        let frames = super::frames("ééüöaé");
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].packet_marker, 1..5);
+        assert_eq!(frames[0].payload_text, "é");
+    }
+
+    #[test]
+    fn test_find_marker_matches_reference() {
+        // xorshift, so the streams are the same on every run.
+        let mut state: u32 = 0x2022_1206;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let ascii: Vec<char> = ('a'..='z').collect();
+        let unicode: Vec<char> = "aé€😀ßΩüö".chars().collect();
+        for alphabet in [
+            &ascii[..2],
+            &ascii[..8],
+            &ascii[..],
+            &unicode[..4],
+            &unicode[..],
+        ] {
+            for _ in 0..5 {
+                let datastream: String = (0..300)
+                    .map(|_| alphabet[next() as usize % alphabet.len()])
+                    .collect();
+                for marker_length in 1..=16 {
+                    assert_eq!(
+                        super::find_marker(&datastream, marker_length),
+                        find_marker_reference(&datastream, marker_length),
+                        "marker length {marker_length} in {datastream}"
+                    );
+                }
+            }
+        }
     }
 }
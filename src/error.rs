@@ -0,0 +1,325 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum Error {
+    /// A failure reading input. `path` is the file being read, when it is known.
+    Io {
+        path: Option<PathBuf>,
+        source: std::io::Error,
+    },
+    /// Malformed puzzle input. `line` and `column` are 1-based, and `text` is the offending line.
+    Parse {
+        day: u8,
+        file: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    /// Well-formed input that does not lead to an answer.
+    Solve {
+        day: u8,
+        reason: String,
+    },
+    Unsolved {
+        day: u8,
+        part: u8,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// A parse error in `text`, which is treated as line 1 until it is offset.
+    pub fn parse(day: u8, text: &str, column: usize, reason: impl Into<String>) -> Error {
+        Error::Parse {
+            day,
+            file: None,
+            line: 1,
+            column,
+            text: text.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn solve(day: u8, reason: impl Into<String>) -> Error {
+        Error::Solve {
+            day,
+            reason: reason.into(),
+        }
+    }
+
+    /// Moves a parse error down by `lines`, for errors raised on a block that does not start at
+    /// the top of the input.
+    pub fn offset_lines(self, lines: usize) -> Error {
+        match self {
+            Error::Parse {
+                day,
+                file,
+                line,
+                column,
+                text,
+                reason,
+            } => Error::Parse {
+                day,
+                file,
+                line: line + lines,
+                column,
+                text,
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    /// Re-anchors a parse error raised on a fragment of `line` that starts at byte `offset`.
+    pub fn within(self, line: &str, offset: usize) -> Error {
+        match self {
+            Error::Parse {
+                day,
+                file,
+                line: line_number,
+                column,
+                reason,
+                ..
+            } => Error::Parse {
+                day,
+                file,
+                line: line_number,
+                column: column + line[..offset].chars().count(),
+                text: line.to_string(),
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    /// Records the file that a parse or I/O error came from.
+    pub fn in_file(self, path: &Path) -> Error {
+        match self {
+            Error::Io { source, .. } => Error::Io {
+                path: Some(path.to_path_buf()),
+                source,
+            },
+            Error::Parse {
+                day,
+                line,
+                column,
+                text,
+                reason,
+                ..
+            } => Error::Parse {
+                day,
+                file: Some(path.to_path_buf()),
+                line,
+                column,
+                text,
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    /// Renders the error in the style of a compiler diagnostic. Parse errors show the offending
+    /// line with a caret under the bad column; other errors are a single line.
+    pub fn diagnostic(&self) -> String {
+        if let Error::Io {
+            path: Some(path),
+            source,
+        } = self
+        {
+            return format!("error: {source}\n --> {}\n", display_path(path));
+        }
+        let Error::Parse {
+            file,
+            line,
+            column,
+            text,
+            reason,
+            ..
+        } = self
+        else {
+            return format!("error: {self}\n");
+        };
+
+        let file = match file {
+            Some(path) => display_path(path),
+            None => "<input>".to_string(),
+        };
+        let gutter = " ".repeat(line.to_string().len());
+        let indent: String = text
+            .chars()
+            .take(column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        format!(
+            "error: {reason}\n{gutter}--> {file}:{line}:{column}\n{gutter} |\n{line} | {text}\n{gutter} | {indent}^\n"
+        )
+    }
+}
+
+fn display_path(path: &Path) -> String {
+    if path == Path::new(crate::util::STDIN_PATH) {
+        "<stdin>".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "{}: {source}", display_path(path)),
+            Error::Io { path: None, source } => write!(f, "{source}"),
+            Error::Parse {
+                day,
+                file: Some(file),
+                line,
+                column,
+                text,
+                reason,
+            } => write!(
+                f,
+                "{}:{line}:{column}: day {day}: {reason} in {text:?}",
+                file.display()
+            ),
+            Error::Parse {
+                day,
+                file: None,
+                line,
+                column,
+                text,
+                reason,
+            } => write!(
+                f,
+                "day {day}, line {line}, column {column}: {reason} in {text:?}"
+            ),
+            Error::Solve { day, reason } => write!(f, "day {day}: {reason}"),
+            Error::Unsolved { day, part } => write!(f, "day {day}, part {part} is not solved"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// `std::io::Error` is not `Clone`, so a cloned I/O error keeps its kind and message only.
+impl Clone for Error {
+    fn clone(&self) -> Self {
+        match self {
+            Error::Io { path, source } => Error::Io {
+                path: path.clone(),
+                source: std::io::Error::new(source.kind(), source.to_string()),
+            },
+            Error::Parse {
+                day,
+                file,
+                line,
+                column,
+                text,
+                reason,
+            } => Error::Parse {
+                day: *day,
+                file: file.clone(),
+                line: *line,
+                column: *column,
+                text: text.clone(),
+                reason: reason.clone(),
+            },
+            Error::Solve { day, reason } => Error::Solve {
+                day: *day,
+                reason: reason.clone(),
+            },
+            Error::Unsolved { day, part } => Error::Unsolved {
+                day: *day,
+                part: *part,
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(source: std::io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    #[test]
+    fn test_offset_lines_and_within() {
+        let err = Error::parse(4, "6-x", 3, "invalid number")
+            .within("2-4,6-x", 4)
+            .offset_lines(2);
+        match err {
+            Error::Parse {
+                day,
+                line,
+                column,
+                text,
+                ..
+            } => {
+                assert_eq!(day, 4);
+                assert_eq!(line, 3);
+                assert_eq!(column, 7);
+                assert_eq!(text, "2-4,6-x");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let err = Error::parse(1, "12a", 1, "invalid calorie count").offset_lines(4);
+        assert_eq!(
+            err.to_string(),
+            "day 1, line 5, column 1: invalid calorie count in \"12a\""
+        );
+        let err = err.in_file(std::path::Path::new("inputs/day01.txt"));
+        assert_eq!(
+            err.to_string(),
+            "inputs/day01.txt:5:1: day 1: invalid calorie count in \"12a\""
+        );
+    }
+
+    #[test]
+    fn test_io_error_names_file() {
+        let source = std::io::Error::new(std::io::ErrorKind::NotFound, "No such file");
+        let err = Error::from(source).in_file(std::path::Path::new("inputs/day03.txt"));
+        assert_eq!(err.to_string(), "inputs/day03.txt: No such file");
+        assert_eq!(
+            err.diagnostic(),
+            "error: No such file\n --> inputs/day03.txt\n"
+        );
+        let err = err.in_file(std::path::Path::new("-"));
+        assert_eq!(err.to_string(), "<stdin>: No such file");
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let err = Error::parse(4, "2-4;6-8", 8, "expected two comma-separated assignments")
+            .offset_lines(9)
+            .in_file(std::path::Path::new("input.txt"));
+        assert_eq!(
+            err.diagnostic(),
+            "error: expected two comma-separated assignments
+  --> input.txt:10:8
+   |
+10 | 2-4;6-8
+   |        ^
+"
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Advent of Code 2022 solutions.
+//!
+//! Each `dayN` module exposes its parsed model types and implements [`solution::Solution`].
+//! [`runner`] registers every day so they can be looked up and run by number.
+
+pub mod answers;
+pub mod bench;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod error;
+pub mod runner;
+pub mod solution;
+pub mod util;
@@ -1,23 +1,122 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod util;
+mod cli;
+mod json;
+mod report;
 
-fn main() -> std::io::Result<()> {
-    let args = std::env::args().collect::<Vec<String>>();
-    if args.len() > 1 {
-        let path = std::path::PathBuf::from(args[1].as_str()).canonicalize()?;
-        let data = util::read_file(path)?;
-        println!(
-            "Characters to process: {}",
-            day6::find_start_of_packet_marker(&data).expect("Cannot find marker")
-        );
-    } else {
-        println!("USAGE: {} [filename]", args[0]);
+use adventofcode2022::error;
+use adventofcode2022::runner::{self, Status};
+use adventofcode2022::solution::Solution;
+use adventofcode2022::{answers, bench, day1, util};
+use cli::{Command, Format};
+
+fn run(command: Command, format: Format) -> error::Result<()> {
+    match command {
+        Command::Run { day, part, path } => {
+            let path = path.unwrap_or_else(|| {
+                runner::input_path(std::path::Path::new(cli::DEFAULT_INPUTS_DIR), day)
+            });
+            let result = runner::run_file(day, part, &path)?;
+            if format == Format::Json {
+                println!("{}", report::part_json(&result));
+                if matches!(result.status, Status::Failed(_)) {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            match result.status {
+                Status::Solved(answer) => println!("Day {day}, part {part}: {answer}"),
+                Status::Failed(err) => return Err(err),
+                Status::Skipped => {}
+            }
+        }
+        Command::Bench {
+            day,
+            iterations,
+            path,
+        } => {
+            let path = path.unwrap_or_else(|| {
+                runner::input_path(std::path::Path::new(cli::DEFAULT_INPUTS_DIR), day)
+            });
+            let result = bench::bench_file(day, &path, iterations)?;
+            match format {
+                Format::Text => print!("{}", report::bench_table(&result)),
+                Format::Json => println!("{}", report::bench_json(&result)),
+            }
+        }
+        Command::Stats { path } => {
+            let path = path.unwrap_or_else(|| {
+                runner::input_path(std::path::Path::new(cli::DEFAULT_INPUTS_DIR), 1)
+            });
+            let data = util::load_input(&path, &day1::CalorieCounting::NORMALIZATION)?;
+            let elves = day1::parse_elf_calories(&data).map_err(|err| err.in_file(&path))?;
+            let stats = day1::CalorieStats::new(&elves).map_err(|err| err.in_file(&path))?;
+            match format {
+                Format::Text => print!("{}", report::calorie_stats_table(&stats)),
+                Format::Json => println!("{}", report::calorie_stats_json(&stats)),
+            }
+        }
+        Command::All { inputs } => {
+            let results = runner::run_all(&inputs);
+            match format {
+                Format::Text => print!("{}", report::table(&results)),
+                Format::Json => {
+                    for result in &results {
+                        println!("{}", report::part_json(result));
+                    }
+                }
+            }
+        }
+        Command::Verify { inputs } => {
+            let verification = match answers::verify(&inputs) {
+                Ok(verification) => verification,
+                Err(err) => {
+                    match format {
+                        Format::Text => eprintln!("{err}"),
+                        Format::Json => println!("{}", report::error_json(None, None, &err)),
+                    }
+                    std::process::exit(1);
+                }
+            };
+            for check in &verification.checks {
+                match format {
+                    Format::Text => {
+                        if let Some(line) = report::verdict_line(check) {
+                            println!("{line}");
+                        }
+                    }
+                    Format::Json => println!("{}", report::verdict_json(check)),
+                }
+            }
+            let failures = verification.failures();
+            if failures > 0 {
+                eprintln!("{failures} part(s) failed verification");
+                std::process::exit(1);
+            }
+        }
     }
 
     Ok(())
 }
+
+fn main() {
+    let mut args = std::env::args();
+    let program = args
+        .next()
+        .unwrap_or_else(|| "adventofcode2022".to_string());
+    let args = match cli::parse_args(args) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}");
+            eprintln!("{}", cli::usage(&program));
+            std::process::exit(2);
+        }
+    };
+
+    let (day, part) = args.command.target();
+    if let Err(err) = run(args.command, args.format) {
+        match args.format {
+            Format::Text => eprint!("{}", err.diagnostic()),
+            Format::Json => println!("{}", report::error_json(day, part, &err.to_string())),
+        }
+        std::process::exit(1);
+    }
+}
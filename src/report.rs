@@ -0,0 +1,389 @@
+use std::time::Duration;
+
+use crate::json::Json;
+use adventofcode2022::answers::{Check, Verdict};
+use adventofcode2022::bench::{BenchResult, Stats};
+use adventofcode2022::day1::CalorieStats;
+use adventofcode2022::runner::{PartResult, Status};
+
+const HEADERS: [&str; 5] = ["Day", "Part", "Answer", "Parse", "Time"];
+
+fn duration(elapsed: Option<Duration>) -> String {
+    match elapsed {
+        Some(elapsed) => format!("{elapsed:.2?}"),
+        None => "-".to_string(),
+    }
+}
+
+fn row(result: &PartResult) -> [String; 5] {
+    let answer = match &result.status {
+        Status::Solved(answer) => answer.clone(),
+        Status::Failed(err) => format!("(error: {err})"),
+        Status::Skipped => "(skipped)".to_string(),
+    };
+    [
+        result.day.to_string(),
+        result.part.to_string(),
+        answer,
+        duration(result.parse_elapsed),
+        duration(result.elapsed),
+    ]
+}
+
+pub fn table(results: &[PartResult]) -> String {
+    let rows: Vec<[String; 5]> = results.iter().map(row).collect();
+    let mut widths = HEADERS.map(|header| header.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    let header = HEADERS.map(|h| h.to_string());
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        let line = format!(
+            "{:>w0$}  {:>w1$}  {:<w2$}  {:>w3$}  {:>w4$}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w4 = widths[4],
+        );
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+fn stats_row(step: &str, stats: &Stats) -> String {
+    format!(
+        "{step:<8}  {:>10.2?}  {:>10.2?}  {:>10.2?}  {:>10.2?}",
+        stats.min, stats.median, stats.mean, stats.p95
+    )
+}
+
+pub fn bench_table(result: &BenchResult) -> String {
+    let mut output = format!(
+        "Day {} ({} iterations)\n{:<8}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+        result.day, result.iterations, "Step", "Min", "Median", "Mean", "p95"
+    );
+    output.push_str(&stats_row("parse", &result.parse));
+    output.push('\n');
+    for (part, stats) in &result.parts {
+        let step = format!("part {part}");
+        match stats {
+            Ok(stats) => output.push_str(&stats_row(&step, stats)),
+            Err(err) => output.push_str(&format!("{step:<8}  (error: {err})")),
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+fn part_fields(result: &PartResult) -> Vec<(&'static str, Json)> {
+    let (status, answer, error) = match &result.status {
+        Status::Solved(answer) => ("solved", Some(answer.clone()), None),
+        Status::Failed(err) => ("failed", None, Some(err.to_string())),
+        Status::Skipped => ("skipped", None, None),
+    };
+    vec![
+        ("day", result.day.into()),
+        ("part", result.part.into()),
+        ("status", status.into()),
+        ("answer", answer.into()),
+        (
+            "parse_ns",
+            result
+                .parse_elapsed
+                .map(|elapsed| elapsed.as_nanos())
+                .into(),
+        ),
+        (
+            "elapsed_ns",
+            result.elapsed.map(|elapsed| elapsed.as_nanos()).into(),
+        ),
+        ("error", error.into()),
+    ]
+}
+
+pub fn part_json(result: &PartResult) -> Json {
+    Json::Object(part_fields(result))
+}
+
+pub fn verdict_json(check: &Check) -> Json {
+    let mut fields = part_fields(&check.result);
+    fields.push(("expected", check.expected.as_deref().into()));
+    fields.push(("verdict", check.verdict.name().into()));
+    Json::Object(fields)
+}
+
+/// The text line for one checked part, or `None` for parts with nothing to check.
+pub fn verdict_line(check: &Check) -> Option<String> {
+    let (day, part) = (check.result.day, check.result.part);
+    let outcome = match &check.verdict {
+        Verdict::Match => "ok".to_string(),
+        Verdict::Mismatch { expected, actual } => {
+            format!("MISMATCH (expected {expected}, got {actual})")
+        }
+        Verdict::Failed {
+            expected: Some(expected),
+            error,
+        } => format!("FAILED (expected {expected}, {error})"),
+        Verdict::Failed {
+            expected: None,
+            error,
+        } => format!("FAILED ({error})"),
+        Verdict::Missing { expected } => format!("MISSING (expected {expected}, no input)"),
+        Verdict::Unrecorded | Verdict::Skipped => return None,
+    };
+    Some(format!("Day {day}, part {part}: {outcome}"))
+}
+
+/// A failure that happened before any part could be solved, such as an unreadable input.
+pub fn error_json(day: Option<u8>, part: Option<u8>, error: &str) -> Json {
+    Json::Object(vec![
+        ("day", day.into()),
+        ("part", part.into()),
+        ("status", "failed".into()),
+        ("error", error.into()),
+    ])
+}
+
+fn stats_json(stats: &Stats) -> Json {
+    Json::Object(vec![
+        ("min_ns", stats.min.as_nanos().into()),
+        ("median_ns", stats.median.as_nanos().into()),
+        ("mean_ns", stats.mean.as_nanos().into()),
+        ("p95_ns", stats.p95.as_nanos().into()),
+    ])
+}
+
+pub fn bench_json(result: &BenchResult) -> Json {
+    let parts = result
+        .parts
+        .iter()
+        .map(|(part, stats)| {
+            let (stats, error) = match stats {
+                Ok(stats) => (stats_json(stats), Json::Null),
+                Err(err) => (Json::Null, err.to_string().into()),
+            };
+            Json::Object(vec![
+                ("part", (*part).into()),
+                ("solve", stats),
+                ("error", error),
+            ])
+        })
+        .collect();
+    Json::Object(vec![
+        ("day", result.day.into()),
+        ("iterations", result.iterations.into()),
+        ("parse", stats_json(&result.parse)),
+        ("parts", Json::Array(parts)),
+    ])
+}
+
+/// The widest histogram bar drawn by [`calorie_stats_table`].
+const HISTOGRAM_WIDTH: usize = 40;
+
+fn join_indices(indices: &[usize]) -> String {
+    indices
+        .iter()
+        .map(|index| index.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn calorie_stats_table(stats: &CalorieStats) -> String {
+    let mut output = format!(
+        "Elves         {}
+Mean          {:.1}
+Median        {:.1}
+Std dev       {:.1}
+Largest item  {}
+Most items    {} (elves {})
+Totals
+",
+        stats.elves,
+        stats.mean,
+        stats.median,
+        stats.std_dev,
+        stats.largest_item,
+        stats.most_items,
+        join_indices(&stats.most_items_elves),
+    );
+    let largest = stats.histogram.iter().map(|b| b.count).max().unwrap_or(0);
+    let low_width = stats
+        .histogram
+        .iter()
+        .map(|b| b.low.to_string().len())
+        .max();
+    let high_width = stats
+        .histogram
+        .iter()
+        .map(|b| b.high.to_string().len())
+        .max();
+    for bucket in &stats.histogram {
+        let bar = "#".repeat((bucket.count * HISTOGRAM_WIDTH).div_ceil(largest.max(1)));
+        let line = format!(
+            "  {:>lw$} - {:>hw$}  {:>3}  {bar}",
+            bucket.low,
+            bucket.high,
+            bucket.count,
+            lw = low_width.unwrap_or(0),
+            hw = high_width.unwrap_or(0),
+        );
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+pub fn calorie_stats_json(stats: &CalorieStats) -> Json {
+    let histogram = stats
+        .histogram
+        .iter()
+        .map(|bucket| {
+            Json::Object(vec![
+                ("low", bucket.low.into()),
+                ("high", bucket.high.into()),
+                ("count", bucket.count.into()),
+            ])
+        })
+        .collect();
+    Json::Object(vec![
+        ("elves", stats.elves.into()),
+        ("mean", stats.mean.into()),
+        ("median", stats.median.into()),
+        ("std_dev", stats.std_dev.into()),
+        ("largest_item", stats.largest_item.into()),
+        ("most_items", stats.most_items.into()),
+        (
+            "most_items_elves",
+            Json::Array(stats.most_items_elves.iter().map(|&i| i.into()).collect()),
+        ),
+        ("histogram", Json::Array(histogram)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use adventofcode2022::runner::{PartResult, Status};
+    use std::time::Duration;
+
+    #[test]
+    fn test_part_json() {
+        let result = PartResult {
+            day: 6,
+            part: 1,
+            status: Status::Failed(adventofcode2022::error::Error::Unsolved { day: 6, part: 1 }),
+            parse_elapsed: None,
+            elapsed: Some(Duration::from_nanos(1500)),
+        };
+        assert_eq!(
+            super::part_json(&result).to_string(),
+            r#"{"day":6,"part":1,"status":"failed","answer":null,"parse_ns":null,"elapsed_ns":1500,"error":"day 6, part 1 is not solved"}"#
+        );
+    }
+
+    #[test]
+    fn test_calorie_stats_table() {
+        let elves =
+            adventofcode2022::day1::parse_elf_calories("1000\n2000\n\n4000\n\n3000").unwrap();
+        let stats = adventofcode2022::day1::CalorieStats::new(&elves).unwrap();
+        assert_eq!(
+            super::calorie_stats_table(&stats),
+            "Elves         3
+Mean          3333.3
+Median        3000.0
+Std dev       471.4
+Largest item  4000
+Most items    2 (elves 0)
+Totals
+  3000 - 3333    2  ########################################
+  3334 - 3667    0
+  3668 - 4000    1  ####################
+"
+        );
+        assert!(super::calorie_stats_json(&stats)
+            .to_string()
+            .starts_with(r#"{"elves":3,"mean":3333.3333333333335,"median":3000,"#));
+    }
+
+    #[test]
+    fn test_error_json() {
+        assert_eq!(
+            super::error_json(Some(9), None, "day 9, part 1 is not solved").to_string(),
+            r#"{"day":9,"part":null,"status":"failed","error":"day 9, part 1 is not solved"}"#
+        );
+    }
+
+    #[test]
+    fn test_verdict_line() {
+        use adventofcode2022::answers::{Check, Verdict};
+
+        let check = |status, expected: Option<&str>, verdict| Check {
+            result: PartResult {
+                day: 3,
+                part: 1,
+                status,
+                parse_elapsed: None,
+                elapsed: None,
+            },
+            expected: expected.map(str::to_string),
+            verdict,
+        };
+        let missing = check(
+            Status::Skipped,
+            Some("157"),
+            Verdict::Missing {
+                expected: "157".to_string(),
+            },
+        );
+        assert_eq!(
+            super::verdict_line(&missing).as_deref(),
+            Some("Day 3, part 1: MISSING (expected 157, no input)")
+        );
+        assert_eq!(
+            super::verdict_json(&missing).to_string(),
+            r#"{"day":3,"part":1,"status":"skipped","answer":null,"parse_ns":null,"elapsed_ns":null,"error":null,"expected":"157","verdict":"missing"}"#
+        );
+        let skipped = check(Status::Skipped, None, Verdict::Skipped);
+        assert_eq!(super::verdict_line(&skipped), None);
+    }
+
+    #[test]
+    fn test_table() {
+        let results = vec![
+            PartResult {
+                day: 1,
+                part: 1,
+                status: Status::Solved("24000".to_string()),
+                parse_elapsed: Some(Duration::from_micros(500)),
+                elapsed: Some(Duration::from_millis(2)),
+            },
+            PartResult {
+                day: 2,
+                part: 1,
+                status: Status::Skipped,
+                parse_elapsed: None,
+                elapsed: None,
+            },
+        ];
+        assert_eq!(
+            super::table(&results),
+            "Day  Part  Answer        Parse    Time
+  1     1  24000      500.00µs  2.00ms
+  2     1  (skipped)         -       -
+"
+        );
+    }
+}
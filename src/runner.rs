@@ -0,0 +1,285 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::Normalization;
+use crate::{day1, day2, day3, day4, day5, day6, util};
+
+pub const PARTS: [u8; 2] = [1, 2];
+
+/// A parsed puzzle input, with the concrete model type erased.
+pub trait Parsed {
+    fn solve(&self, part: u8) -> Result<String>;
+}
+
+struct Model<S: Solution>(S::Model);
+
+impl<S: Solution> Parsed for Model<S> {
+    fn solve(&self, part: u8) -> Result<String> {
+        match part {
+            1 => S::part1(&self.0).map(|answer| answer.to_string()),
+            2 => S::part2(&self.0).map(|answer| answer.to_string()),
+            _ => Err(Error::Unsolved { day: S::DAY, part }),
+        }
+    }
+}
+
+fn parse<S: Solution + 'static>(input: &str) -> Result<Box<dyn Parsed>> {
+    Ok(Box::new(Model::<S>(S::parse(input)?)))
+}
+
+pub struct Day {
+    pub number: u8,
+    pub normalization: Normalization,
+    parse: fn(&str) -> Result<Box<dyn Parsed>>,
+}
+
+impl Day {
+    pub const fn new<S: Solution + 'static>() -> Day {
+        Day {
+            number: S::DAY,
+            normalization: S::NORMALIZATION,
+            parse: parse::<S>,
+        }
+    }
+
+    /// Reads the day's input from `path`, normalized with the day's policy.
+    pub fn load_input(&self, path: &Path) -> Result<String> {
+        util::load_input(path, &self.normalization)
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Parsed>> {
+        (self.parse)(input)
+    }
+
+    pub fn solve(&self, part: u8, input: &str) -> Result<String> {
+        self.parse(input)?.solve(part)
+    }
+
+    /// Parses the input once and solves each of `parts`, timing the parse separately from each
+    /// part. If the input does not parse, every part fails with the parse error.
+    pub fn run_parts(&self, parts: &[u8], input: &str) -> Vec<PartResult> {
+        let start = Instant::now();
+        let parsed = self.parse(input);
+        let parse_elapsed = Some(start.elapsed());
+        parts
+            .iter()
+            .map(|&part| {
+                let (status, elapsed) = match &parsed {
+                    Ok(parsed) => {
+                        let start = Instant::now();
+                        let status = match parsed.solve(part) {
+                            Ok(answer) => Status::Solved(answer),
+                            Err(err) => Status::Failed(err),
+                        };
+                        (status, Some(start.elapsed()))
+                    }
+                    Err(err) => (Status::Failed(err.clone()), None),
+                };
+                PartResult {
+                    day: self.number,
+                    part,
+                    status,
+                    parse_elapsed,
+                    elapsed,
+                }
+            })
+            .collect()
+    }
+
+    pub fn run(&self, part: u8, input: &str) -> PartResult {
+        self.run_parts(&[part], input)
+            .pop()
+            .expect("one part was run")
+    }
+}
+
+pub enum Status {
+    Solved(String),
+    Failed(Error),
+    Skipped,
+}
+
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    pub status: Status,
+    /// How long parsing the day's input took. Every part of a day shares the same parse.
+    pub parse_elapsed: Option<Duration>,
+    /// How long solving this part took, not counting the parse.
+    pub elapsed: Option<Duration>,
+}
+
+pub const DAYS: &[Day] = &[
+    Day::new::<day1::CalorieCounting>(),
+    Day::new::<day2::RockPaperScissors>(),
+    Day::new::<day3::RucksackReorganization>(),
+    Day::new::<day4::CampCleanup>(),
+    Day::new::<day5::SupplyStacks>(),
+    Day::new::<day6::TuningTrouble>(),
+];
+
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+/// Loads `path` and solves one part of `day` from it. Errors name the file they came from.
+pub fn run_file(day: u8, part: u8, path: &Path) -> Result<PartResult> {
+    let Some(solution) = find_day(day) else {
+        return Err(Error::Unsolved { day, part });
+    };
+    let data = solution.load_input(path)?;
+    let mut result = solution.run(part, &data);
+    if let Status::Failed(err) = result.status {
+        result.status = Status::Failed(err.in_file(path));
+    }
+    Ok(result)
+}
+
+pub fn input_path(inputs: &Path, day: u8) -> PathBuf {
+    inputs.join(format!("day{day:02}.txt"))
+}
+
+/// Runs both parts of every registered day against `inputs/dayNN.txt`. Days without an input
+/// file are reported as skipped, and an input that cannot be read fails only its own day.
+pub fn run_all(inputs: &Path) -> Vec<PartResult> {
+    let mut results = vec![];
+    for day in DAYS {
+        let path = input_path(inputs, day.number);
+        if !path.exists() {
+            results.extend(PARTS.iter().map(|&part| PartResult {
+                day: day.number,
+                part,
+                status: Status::Skipped,
+                parse_elapsed: None,
+                elapsed: None,
+            }));
+            continue;
+        }
+        let data = match day.load_input(&path) {
+            Ok(data) => data,
+            Err(err) => {
+                let err = err.in_file(&path);
+                results.extend(PARTS.iter().map(|&part| PartResult {
+                    day: day.number,
+                    part,
+                    status: Status::Failed(err.clone()),
+                    parse_elapsed: None,
+                    elapsed: None,
+                }));
+                continue;
+            }
+        };
+        results.extend(day.run_parts(&PARTS, &data).into_iter().map(|mut result| {
+            if let Status::Failed(err) = result.status {
+                result.status = Status::Failed(err.in_file(&path));
+            }
+            result
+        }));
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_days_are_unique() {
+        for (idx, day) in super::DAYS.iter().enumerate() {
+            assert!(super::DAYS[idx + 1..]
+                .iter()
+                .all(|other| other.number != day.number));
+        }
+    }
+
+    #[test]
+    fn test_find_day() {
+        let day = super::find_day(4).expect("day 4 is registered");
+        assert_eq!(day.number, 4);
+        let parsed = day.parse("2-4,6-8\n2-8,3-7").unwrap();
+        assert_eq!(parsed.solve(1).unwrap(), "1");
+        assert_eq!(parsed.solve(2).unwrap(), "1");
+        assert!(parsed.solve(3).is_err());
+        assert!(super::find_day(25).is_none());
+    }
+
+    #[test]
+    fn test_run_parts() {
+        let day = super::find_day(4).unwrap();
+        let results = day.run_parts(&super::PARTS, "2-4,6-8\n2-8,3-7");
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.parse_elapsed.is_some()
+            && matches!(&result.status, super::Status::Solved(answer) if answer == "1")));
+
+        let results = day.run_parts(&super::PARTS, "2-4;6-8");
+        assert!(results
+            .iter()
+            .all(|result| result.elapsed.is_none()
+                && matches!(result.status, super::Status::Failed(_))));
+    }
+
+    #[test]
+    fn test_load_input_uses_day_policy() {
+        let path = std::env::temp_dir().join(format!("aoc2022-day-policy-{}", std::process::id()));
+        std::fs::write(&path, "    [D] \n2-8,3-7\t\n \n").unwrap();
+        let day4 = super::find_day(4).unwrap().load_input(&path);
+        let day5 = super::find_day(5).unwrap().load_input(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(day4.unwrap(), "    [D]\n2-8,3-7");
+        assert_eq!(day5.unwrap(), "    [D] \n2-8,3-7");
+
+        // Only day 5 departs from the default that `util::read_file` and the tests use.
+        for day in super::DAYS {
+            let is_default = day.normalization == super::Normalization::default();
+            assert_eq!(is_default, day.number != 5, "day {}", day.number);
+        }
+    }
+
+    #[test]
+    fn test_run_file() {
+        let path = std::env::temp_dir().join(format!("aoc2022-run-file-{}", std::process::id()));
+        std::fs::write(&path, "2-4,6-8\n2-8,3-7\n").unwrap();
+        let solved = super::run_file(4, 1, &path);
+        let unsolved = super::run_file(25, 1, &path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(solved.unwrap().status, super::Status::Solved(answer) if answer == "1"));
+        assert!(unsolved.is_err());
+    }
+
+    #[test]
+    fn test_input_path() {
+        let path = super::input_path(std::path::Path::new("inputs"), 4);
+        assert_eq!(path, std::path::PathBuf::from("inputs/day04.txt"));
+    }
+
+    #[test]
+    fn test_run_all_continues_after_unreadable_input() {
+        let inputs = std::env::temp_dir().join(format!("aoc2022-run-all-{}", std::process::id()));
+        std::fs::create_dir_all(&inputs).unwrap();
+        std::fs::write(super::input_path(&inputs, 2), b"\xff\xfe").unwrap();
+        std::fs::write(super::input_path(&inputs, 4), "2-4,6-8\n2-8,3-7").unwrap();
+        let results = super::run_all(&inputs);
+        std::fs::remove_dir_all(&inputs).unwrap();
+
+        let status = |day: u8, part: u8| {
+            &results
+                .iter()
+                .find(|result| result.day == day && result.part == part)
+                .unwrap()
+                .status
+        };
+        assert!(matches!(status(2, 1), super::Status::Failed(_)));
+        assert!(matches!(status(2, 2), super::Status::Failed(_)));
+        assert!(matches!(status(4, 1), super::Status::Solved(answer) if answer == "1"));
+    }
+
+    #[test]
+    fn test_run_all_skips_missing_inputs() {
+        let results = super::run_all(std::path::Path::new("/nonexistent"));
+        assert_eq!(results.len(), super::DAYS.len() * super::PARTS.len());
+        assert!(results
+            .iter()
+            .all(|result| matches!(result.status, super::Status::Skipped)));
+    }
+}
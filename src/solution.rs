@@ -0,0 +1,22 @@
+use std::fmt::Display;
+
+use crate::error::Result;
+use crate::util::Normalization;
+
+/// A single day's puzzle. The input is parsed once into `Model`, and each part computes its
+/// answer from that model.
+pub trait Solution {
+    const DAY: u8;
+
+    /// How the input is cleaned up before [`Solution::parse`] sees it.
+    const NORMALIZATION: Normalization = Normalization::DEFAULT;
+
+    type Model: 'static;
+    type Answer: Display;
+
+    fn parse(input: &str) -> Result<Self::Model>;
+
+    fn part1(model: &Self::Model) -> Result<Self::Answer>;
+
+    fn part2(model: &Self::Model) -> Result<Self::Answer>;
+}
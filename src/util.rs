@@ -1,25 +1,253 @@
 use std::fs::File;
 use std::io::{read_to_string, BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::Path;
 
-fn buffered_file(path: PathBuf) -> std::io::Result<BufReader<File>> {
+use crate::error::{Error, Result};
+
+/// The path that stands for standard input rather than a file.
+pub const STDIN_PATH: &str = "-";
+
+fn buffered_file<P: AsRef<Path>>(path: P) -> std::io::Result<Box<dyn BufRead>> {
+    let path = path.as_ref();
+    if path == Path::new(STDIN_PATH) {
+        return Ok(Box::new(BufReader::new(std::io::stdin())));
+    }
     let f = File::open(path)?;
-    Ok(BufReader::new(f))
+    Ok(Box::new(BufReader::new(f)))
+}
+
+/// What [`normalize`] does with the newlines at the end of the input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrailingNewline {
+    Keep,
+    /// Removes all whitespace at the end of the input, including spaces or tabs left on a final
+    /// blank line, so the last line is not followed by an empty one.
+    Trim,
+}
+
+/// How input is cleaned up before a day parses it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Normalization {
+    /// Removes a leading UTF-8 byte order mark.
+    pub strip_bom: bool,
+    /// Converts `\r\n` line endings to `\n`.
+    pub convert_crlf: bool,
+    pub trailing_newline: TrailingNewline,
+    /// Removes whitespace at the end of every line. On by default; day 5 turns it off through
+    /// [`Solution::NORMALIZATION`](crate::solution::Solution::NORMALIZATION), because its drawing
+    /// pads rows with spaces.
+    pub strip_trailing_whitespace: bool,
+}
+
+impl Normalization {
+    /// The same as [`Normalization::default`], usable in constants.
+    pub const DEFAULT: Normalization = Normalization {
+        strip_bom: true,
+        convert_crlf: true,
+        trailing_newline: TrailingNewline::Trim,
+        strip_trailing_whitespace: true,
+    };
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Normalization::DEFAULT
+    }
+}
+
+pub fn normalize(data: &str, policy: &Normalization) -> String {
+    let data = if policy.strip_bom {
+        data.strip_prefix('\u{feff}').unwrap_or(data)
+    } else {
+        data
+    };
+    let mut data = if policy.convert_crlf {
+        data.replace("\r\n", "\n")
+    } else {
+        data.to_string()
+    };
+    if policy.strip_trailing_whitespace {
+        data = data
+            .split('\n')
+            .map(str::trim_end)
+            .collect::<Vec<&str>>()
+            .join("\n");
+    }
+    if policy.trailing_newline == TrailingNewline::Trim {
+        let len = data.trim_end().len();
+        data.truncate(len);
+    }
+
+    data
+}
+
+/// Normalizes one line of an input that is read line by line rather than through [`load_input`].
+/// `index` is the zero-based line number, and the line must already be split off without its
+/// `\n` or `\r\n`, as [`BufRead::lines`] does. Blank lines at the end are left to the caller.
+pub fn normalize_line<'a>(line: &'a str, index: usize, policy: &Normalization) -> &'a str {
+    let line = if index == 0 && policy.strip_bom {
+        line.strip_prefix('\u{feff}').unwrap_or(line)
+    } else {
+        line
+    };
+    if policy.strip_trailing_whitespace {
+        line.trim_end()
+    } else {
+        line
+    }
+}
+
+/// Reads a whole input file, or standard input for [`STDIN_PATH`], and normalizes it.
+pub fn load_input<P: AsRef<Path>>(path: P, policy: &Normalization) -> Result<String> {
+    let path = path.as_ref();
+    let data = buffered_file(path)
+        .and_then(read_to_string)
+        .map_err(|err| Error::from(err).in_file(path))?;
+    Ok(normalize(&data, policy))
+}
+
+/// Loads a file with the default [`Normalization`]. Day inputs are loaded with the day's own
+/// policy by [`Day::load_input`](crate::runner::Day::load_input).
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<String> {
+    load_input(path, &Normalization::default())
 }
 
-pub fn read_file(path: PathBuf) -> std::io::Result<String> {
-    let reader = buffered_file(path)?;
-    Ok(read_to_string(reader)?.trim_end().to_string())
+pub fn lines_for_file<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+    Ok(lines(&read_file(path)?))
 }
 
-pub fn lines_for_file(path: PathBuf) -> std::io::Result<Vec<String>> {
-    let reader = buffered_file(path)?;
-    Ok(reader
-        .lines()
-        .map(|line| line.expect("line contains invalid UTF-8"))
-        .collect())
+/// Splits normalized input into lines.
+pub fn lines(data: &str) -> Vec<String> {
+    data.split("\n").map(|s| s.to_string()).collect()
 }
 
+/// A run of non-blank lines, as found by [`blocks`].
+#[derive(Debug, PartialEq)]
+pub struct Block<'a> {
+    /// The line number of the first line in the block, counting from 1.
+    pub line: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl Block<'_> {
+    /// The block's lines joined with `\n`, whatever the input used.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+pub struct Blocks<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = Block<'a>;
+
+    fn next(&mut self) -> Option<Block<'a>> {
+        let (start, first) = self.lines.find(|(_, line)| !line.trim().is_empty())?;
+        let mut lines = vec![first];
+        for (_, line) in self.lines.by_ref() {
+            if line.trim().is_empty() {
+                break;
+            }
+            lines.push(line);
+        }
+
+        Some(Block {
+            line: start + 1,
+            lines,
+        })
+    }
+}
+
+/// Splits `data` into blocks separated by blank lines. Lines may end in `\n` or `\r\n`, any run
+/// of empty or whitespace-only lines separates two blocks, and blank lines at the start or end
+/// are ignored.
+pub fn blocks(data: &str) -> Blocks<'_> {
+    Blocks {
+        lines: data.lines().enumerate(),
+    }
+}
+
+#[cfg(test)]
 pub fn testcase_to_input(testcase: &str) -> Vec<String> {
-    testcase.split("\n").map(|s| s.to_string()).collect()
+    lines(&normalize(testcase, &Normalization::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks, normalize, Block, Normalization, TrailingNewline};
+
+    #[test]
+    fn test_blocks() {
+        let found: Vec<Block> = blocks("\n1\r\n2\r\n\r\n  \t\n\n3\n\n\n").collect();
+        assert_eq!(
+            found,
+            vec![
+                Block {
+                    line: 2,
+                    lines: vec!["1", "2"]
+                },
+                Block {
+                    line: 7,
+                    lines: vec!["3"]
+                },
+            ]
+        );
+        assert_eq!(found[0].text(), "1\n2");
+        assert_eq!(blocks(" \n\n").next(), None);
+    }
+
+    #[test]
+    fn test_normalize_default() {
+        let policy = Normalization::default();
+        assert_eq!(normalize("\u{feff}a \r\nb\r\n\r\n", &policy), "a\nb");
+        assert_eq!(normalize("a\n", &policy), normalize("a", &policy));
+        assert_eq!(normalize("", &policy), "");
+        assert_eq!(normalize("1\n2\n \n", &policy), "1\n2");
+        assert_eq!(normalize("1-2,3-4\t", &policy), "1-2,3-4");
+    }
+
+    #[test]
+    fn test_normalize_policy() {
+        let keep_everything = Normalization {
+            strip_bom: false,
+            convert_crlf: false,
+            trailing_newline: TrailingNewline::Keep,
+            strip_trailing_whitespace: false,
+        };
+        let raw = "\u{feff}a \r\nb\t\r\n";
+        assert_eq!(normalize(raw, &keep_everything), raw);
+        let strip_whitespace = Normalization {
+            trailing_newline: TrailingNewline::Keep,
+            ..Normalization::default()
+        };
+        assert_eq!(normalize(raw, &strip_whitespace), "a\nb\n");
+        let keep_whitespace = Normalization {
+            strip_trailing_whitespace: false,
+            ..Normalization::default()
+        };
+        assert_eq!(normalize(raw, &keep_whitespace), "a \nb");
+    }
+
+    #[test]
+    fn test_normalize_line() {
+        let policy = Normalization::default();
+        assert_eq!(super::normalize_line("\u{feff}1 \t", 0, &policy), "1");
+        assert_eq!(super::normalize_line("\u{feff}1", 1, &policy), "\u{feff}1");
+        let keep = Normalization {
+            strip_trailing_whitespace: false,
+            ..Normalization::default()
+        };
+        assert_eq!(super::normalize_line("\u{feff}1 ", 0, &keep), "1 ");
+    }
+
+    #[test]
+    fn test_load_input() {
+        let path = std::env::temp_dir().join(format!("aoc2022-load-input-{}", std::process::id()));
+        std::fs::write(&path, "\u{feff}1\r\n2\r\n").unwrap();
+        let lines = super::lines_for_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(lines.unwrap(), vec!["1", "2"]);
+    }
 }